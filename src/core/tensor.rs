@@ -13,9 +13,24 @@ pub struct SpatialStructure {
     pub data: Option<Array1<f64>>, // Tensor data storage
 }
 
+/// First-parent lineage of version IDs in a `PhylogeneticTree`, root first
 #[derive(Debug, Clone)]
 pub struct PhylogeneticPath {
     pub versions: Vec<usize>,
+    /// Identity of the tree the versions belong to
+    pub tree: Option<u64>,
+}
+
+impl PhylogeneticPath {
+    /// Version the tensor currently sits at
+    pub fn current(&self) -> Option<usize> {
+        self.versions.last().copied()
+    }
+
+    /// Number of versions since the root
+    pub fn generation(&self) -> usize {
+        self.versions.len().saturating_sub(1)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -58,7 +73,7 @@ impl MorphicTensor {
     pub fn void() -> Self {
         MorphicTensor {
             spatial: SpatialStructure::default(),
-            temporal: PhylogeneticPath { versions: Vec::new() },
+            temporal: PhylogeneticPath { versions: Vec::new(), tree: None },
             entanglement: EntanglementField { connections: Vec::new(), strength: 0.0 },
             potential: MorphicGradient { values: DVector::zeros(0) },
             observer: ObserverPerspective { weights: [1.0, 1.0] },
@@ -144,18 +159,6 @@ impl MorphicTensor {
     
     // ... existing methods ...
     
-    /// Applies a developmental delta to the tensor
-    pub fn apply_phylogenetic_delta(&mut self, delta: &[usize]) {
-        // Apply delta to the tensor data if it exists
-        if let Some(data) = &mut self.spatial.data {
-            for &idx in delta {
-                if idx < data.len() {
-                    data[idx] += 1.0; // Simple increment for demonstration
-                }
-            }
-        }
-    }
-    
     /// Creates a quantum fork of the current tensor
     pub fn quantum_fork(&self) -> Self {
         let mut forked = self.clone();
@@ -1,5 +1,6 @@
 // Morphic Tensor Implementation Test
 use morph::core::tensor::MorphicTensor;
+use morph::phylogenetic::{Delta, DeltaOp};
 use morph::quantum::state::QuantumSystem;
 
 fn main() {
@@ -10,8 +11,20 @@ fn main() {
     println!("Void tensor created successfully! State: {:?}", void_tensor.quantum_state);
 
     // Test phylogenetic delta
-    void_tensor.apply_phylogenetic_delta(&[1, 2, 3]);
-    println!("Applied phylogenetic delta: {:?}", void_tensor.temporal.versions);
+    match Delta::increments(&[1, 2, 3]).apply(&mut void_tensor) {
+        Ok(undo) => println!("Applied phylogenetic delta, undo: {:?}", undo),
+        Err(e) => println!("Phylogenetic delta rejected: {}", e),
+    }
+    let mut data_tensor = MorphicTensor::from_data(ndarray::array![1.0, 2.0, 3.0, 4.0]);
+    let delta = Delta::new(vec![
+        DeltaOp::Scale { index: 0, factor: 3.0 },
+        DeltaOp::Add { index: 3, amount: -1.0 },
+        DeltaOp::Translate([0.5, 0.5]),
+    ]);
+    let undo = delta.apply(&mut data_tensor).expect("delta fits tensor");
+    println!("Applied typed delta: {:?} at {:?}", data_tensor.to_array().to_vec(), data_tensor.position());
+    undo.apply(&mut data_tensor).expect("undo fits tensor");
+    println!("Undone delta: {:?} at {:?}", data_tensor.to_array().to_vec(), data_tensor.position());
 
     // Test quantum fork
     let forked_tensor = void_tensor.quantum_fork();
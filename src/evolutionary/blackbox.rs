@@ -0,0 +1,432 @@
+// Black-box optimisers over bounded parameter vectors
+#![allow(dead_code)]
+
+use crate::evolutionary::gaussian;
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Box constraints on a parameter vector
+#[derive(Debug, Clone)]
+pub struct Bounds {
+    pub lower: Array1<f64>,
+    pub upper: Array1<f64>,
+}
+
+impl Bounds {
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Result<Self, String> {
+        if lower.len() != upper.len() {
+            return Err(format!("Bounds have {} lower and {} upper values", lower.len(), upper.len()));
+        }
+        if let Some(i) = (0..lower.len()).find(|&i| lower[i].is_nan() || upper[i].is_nan() || lower[i] > upper[i]) {
+            return Err(format!("Invalid bounds [{}, {}] for parameter {}", lower[i], upper[i], i));
+        }
+        Ok(Bounds { lower: Array1::from_vec(lower), upper: Array1::from_vec(upper) })
+    }
+
+    /// Same interval for every parameter
+    pub fn uniform(dimension: usize, lower: f64, upper: f64) -> Result<Self, String> {
+        Self::new(vec![lower; dimension], vec![upper; dimension])
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.lower.len()
+    }
+
+    pub fn width(&self) -> Array1<f64> {
+        &self.upper - &self.lower
+    }
+
+    pub fn clamp(&self, x: &Array1<f64>) -> Array1<f64> {
+        Array1::from_shape_fn(x.len(), |i| x[i].clamp(self.lower[i], self.upper[i]))
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Array1<f64> {
+        Array1::from_shape_fn(self.dimension(), |i| self.lower[i] + rng.gen::<f64>() * (self.upper[i] - self.lower[i]))
+    }
+
+    /// Map a point of the unit cube into the bounds
+    fn scale(&self, unit: &DVector<f64>) -> Array1<f64> {
+        Array1::from_shape_fn(self.dimension(), |i| self.lower[i] + unit[i].clamp(0.0, 1.0) * (self.upper[i] - self.lower[i]))
+    }
+
+    fn center(&self) -> Array1<f64> {
+        (&self.lower + &self.upper) / 2.0
+    }
+}
+
+/// Outcome of an optimisation run; fitness is maximised
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    pub best: Array1<f64>,
+    pub fitness: f64,
+    pub evaluations: usize,
+    /// Best fitness after each iteration
+    pub history: Vec<f64>,
+}
+
+impl OptimizationResult {
+    fn new(dimension: usize) -> Self {
+        OptimizationResult {
+            best: Array1::zeros(dimension),
+            fitness: f64::NEG_INFINITY,
+            evaluations: 0,
+            history: Vec::new(),
+        }
+    }
+
+    /// Evaluate `x`, treating non-finite fitness as the worst possible
+    fn evaluate(&mut self, x: &Array1<f64>, fitness: &mut dyn FnMut(&Array1<f64>) -> f64) -> f64 {
+        self.evaluations += 1;
+        let value = fitness(x);
+        let value = if value.is_nan() { f64::NEG_INFINITY } else { value };
+        if value > self.fitness || self.evaluations == 1 {
+            self.fitness = value;
+            self.best = x.clone();
+        }
+        value
+    }
+}
+
+/// Derivative-free maximiser of a fitness callback within bounds
+pub trait BlackBoxOptimizer {
+    fn name(&self) -> &str;
+
+    /// Maximise `fitness` using at most `budget` evaluations
+    fn optimize(&mut self, bounds: &Bounds, fitness: &mut dyn FnMut(&Array1<f64>) -> f64, budget: usize) -> OptimizationResult;
+}
+
+/// Covariance matrix adaptation evolution strategy
+///
+/// Searches the unit cube spanned by the bounds; samples leaving it are repaired by
+/// clamping before evaluation and update. Only whole generations are evaluated, so
+/// up to `population_size − 1` evaluations of the budget may go unused.
+pub struct CmaEs {
+    /// Initial step size relative to the bounds' width
+    pub sigma: f64,
+    /// Offspring per generation; `0` uses `4 + ⌊3 ln n⌋`
+    pub population_size: usize,
+    /// Starting mean; the centre of the bounds when `None`
+    pub initial: Option<Array1<f64>>,
+    /// Stop once the search distribution is narrower than this
+    pub tolerance: f64,
+    rng: StdRng,
+}
+
+impl CmaEs {
+    pub fn new(sigma: f64) -> Self {
+        CmaEs {
+            sigma,
+            population_size: 0,
+            initial: None,
+            tolerance: 1e-12,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Make the sampled offspring reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl BlackBoxOptimizer for CmaEs {
+    fn name(&self) -> &str {
+        "CMA-ES"
+    }
+
+    fn optimize(&mut self, bounds: &Bounds, fitness: &mut dyn FnMut(&Array1<f64>) -> f64, budget: usize) -> OptimizationResult {
+        let n = bounds.dimension();
+        let mut result = OptimizationResult::new(n);
+        if n == 0 || budget == 0 {
+            return result;
+        }
+        let rng = &mut self.rng;
+        let nf = n as f64;
+
+        // Strategy parameters (Hansen's defaults)
+        let lambda = if self.population_size > 0 { self.population_size.max(2) } else { 4 + (3.0 * nf.ln()).floor() as usize };
+        let mu = lambda / 2;
+        let raw: Vec<f64> = (0..mu).map(|i| (mu as f64 + 0.5).ln() - ((i + 1) as f64).ln()).collect();
+        let total: f64 = raw.iter().sum();
+        let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+        let mueff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+        let cc = (4.0 + mueff / nf) / (nf + 4.0 + 2.0 * mueff / nf);
+        let cs = (mueff + 2.0) / (nf + mueff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((nf + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+        let width = bounds.width();
+        let start = self.initial.clone().unwrap_or_else(|| bounds.center());
+        let mut mean = DVector::from_fn(n, |i, _| {
+            if width[i] > 0.0 { ((start[i] - bounds.lower[i]) / width[i]).clamp(0.0, 1.0) } else { 0.0 }
+        });
+        let mut sigma = self.sigma;
+        let mut covariance = DMatrix::<f64>::identity(n, n);
+        let mut path_c = DVector::<f64>::zeros(n);
+        let mut path_s = DVector::<f64>::zeros(n);
+        let mut generation = 0;
+
+        while result.evaluations + lambda <= budget {
+            let eigen = SymmetricEigen::new(covariance.clone());
+            let scales = eigen.eigenvalues.map(|v| v.max(1e-20).sqrt());
+            let basis = eigen.eigenvectors;
+
+            let mut offspring: Vec<(DVector<f64>, f64)> = (0..lambda)
+                .map(|_| {
+                    let z = DVector::from_fn(n, |_, _| gaussian(rng));
+                    let y = (&mean + &basis * z.component_mul(&scales) * sigma).map(|v| v.clamp(0.0, 1.0));
+                    let value = result.evaluate(&bounds.scale(&y), fitness);
+                    (y, value)
+                })
+                .collect();
+            offspring.sort_by(|a, b| b.1.total_cmp(&a.1));
+            result.history.push(result.fitness);
+            generation += 1;
+
+            let previous = mean.clone();
+            mean = offspring.iter().take(mu).zip(&weights).fold(DVector::zeros(n), |acc, ((y, _), w)| acc + y * *w);
+            let step = (&mean - &previous) / sigma;
+
+            let inverse_sqrt = &basis * DMatrix::from_diagonal(&scales.map(|s| 1.0 / s)) * basis.transpose();
+            path_s = path_s * (1.0 - cs) + &inverse_sqrt * &step * (cs * (2.0 - cs) * mueff).sqrt();
+            let norm_s = path_s.norm();
+            let hsig = norm_s / (1.0 - (1.0 - cs).powi(2 * generation)).sqrt() / chi_n < 1.4 + 2.0 / (nf + 1.0);
+            let hsig = if hsig { 1.0 } else { 0.0 };
+            path_c = path_c * (1.0 - cc) + &step * (hsig * (cc * (2.0 - cc) * mueff).sqrt());
+
+            let rank_mu = offspring.iter().take(mu).zip(&weights).fold(DMatrix::zeros(n, n), |acc, ((y, _), w)| {
+                let d = (y - &previous) / sigma;
+                acc + &d * d.transpose() * *w
+            });
+            covariance = &covariance * (1.0 - c1 - cmu)
+                + (&path_c * path_c.transpose() + &covariance * ((1.0 - hsig) * cc * (2.0 - cc))) * c1
+                + rank_mu * cmu;
+            covariance = (&covariance + covariance.transpose()) * 0.5;
+            sigma *= ((cs / damps) * (norm_s / chi_n - 1.0)).exp();
+
+            if sigma * scales.max() < self.tolerance {
+                break;
+            }
+        }
+        result
+    }
+}
+
+/// Differential evolution, DE/rand/1/bin
+pub struct DifferentialEvolution {
+    pub population_size: usize,
+    /// Differential weight F
+    pub weight: f64,
+    /// Crossover probability CR
+    pub crossover: f64,
+    rng: StdRng,
+}
+
+impl DifferentialEvolution {
+    pub fn new(population_size: usize) -> Self {
+        DifferentialEvolution {
+            population_size,
+            weight: 0.8,
+            crossover: 0.9,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Make the initial population and mutations reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl BlackBoxOptimizer for DifferentialEvolution {
+    fn name(&self) -> &str {
+        "Differential evolution"
+    }
+
+    fn optimize(&mut self, bounds: &Bounds, fitness: &mut dyn FnMut(&Array1<f64>) -> f64, budget: usize) -> OptimizationResult {
+        let n = bounds.dimension();
+        let mut result = OptimizationResult::new(n);
+        let size = self.population_size.max(4);
+        if n == 0 || budget == 0 {
+            return result;
+        }
+        let rng = &mut self.rng;
+        let mut population: Vec<Array1<f64>> = (0..size).map(|_| bounds.sample(rng)).collect();
+        let mut scores: Vec<f64> = Vec::with_capacity(size);
+        for x in population.iter().take(budget) {
+            scores.push(result.evaluate(x, fitness));
+        }
+        population.truncate(scores.len());
+        result.history.push(result.fitness);
+        if population.len() < 4 {
+            return result;
+        }
+
+        while result.evaluations < budget {
+            for i in 0..population.len() {
+                if result.evaluations >= budget {
+                    break;
+                }
+                // Three distinct members other than the target
+                let mut picks = [i; 3];
+                for k in 0..3 {
+                    while picks[k] == i || picks[..k].contains(&picks[k]) {
+                        picks[k] = rng.gen_range(0..population.len());
+                    }
+                }
+                let [a, b, c] = picks;
+                let forced = rng.gen_range(0..n);
+                let trial = Array1::from_shape_fn(n, |j| {
+                    if j == forced || rng.gen::<f64>() < self.crossover {
+                        population[a][j] + self.weight * (population[b][j] - population[c][j])
+                    } else {
+                        population[i][j]
+                    }
+                });
+                let trial = bounds.clamp(&trial);
+                let value = result.evaluate(&trial, fitness);
+                if value >= scores[i] {
+                    population[i] = trial;
+                    scores[i] = value;
+                }
+            }
+            result.history.push(result.fitness);
+        }
+        result
+    }
+}
+
+/// Particle swarm optimisation with inertia weight
+pub struct ParticleSwarm {
+    pub swarm_size: usize,
+    pub inertia: f64,
+    /// Pull towards each particle's own best
+    pub cognitive: f64,
+    /// Pull towards the swarm's best
+    pub social: f64,
+    /// Largest velocity as a fraction of the bounds' width
+    pub max_velocity: f64,
+    rng: StdRng,
+}
+
+impl ParticleSwarm {
+    pub fn new(swarm_size: usize) -> Self {
+        ParticleSwarm {
+            swarm_size,
+            inertia: 0.729,
+            cognitive: 1.494,
+            social: 1.494,
+            max_velocity: 0.2,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Make the initial swarm and its random pulls reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl BlackBoxOptimizer for ParticleSwarm {
+    fn name(&self) -> &str {
+        "Particle swarm"
+    }
+
+    fn optimize(&mut self, bounds: &Bounds, fitness: &mut dyn FnMut(&Array1<f64>) -> f64, budget: usize) -> OptimizationResult {
+        let n = bounds.dimension();
+        let mut result = OptimizationResult::new(n);
+        if n == 0 || budget == 0 || self.swarm_size == 0 {
+            return result;
+        }
+        let rng = &mut self.rng;
+        let limit = bounds.width() * self.max_velocity;
+
+        let mut positions: Vec<Array1<f64>> = (0..self.swarm_size.min(budget)).map(|_| bounds.sample(rng)).collect();
+        let mut velocities: Vec<Array1<f64>> = positions.iter()
+            .map(|_| Array1::from_shape_fn(n, |j| limit[j] * (2.0 * rng.gen::<f64>() - 1.0)))
+            .collect();
+        let mut personal: Vec<(Array1<f64>, f64)> = positions.iter()
+            .map(|x| (x.clone(), result.evaluate(x, fitness)))
+            .collect();
+        result.history.push(result.fitness);
+
+        while result.evaluations < budget {
+            for i in 0..positions.len() {
+                if result.evaluations >= budget {
+                    break;
+                }
+                let global = result.best.clone();
+                for j in 0..n {
+                    let (r1, r2): (f64, f64) = (rng.gen(), rng.gen());
+                    let v = self.inertia * velocities[i][j]
+                        + self.cognitive * r1 * (personal[i].0[j] - positions[i][j])
+                        + self.social * r2 * (global[j] - positions[i][j]);
+                    velocities[i][j] = v.clamp(-limit[j], limit[j]);
+                }
+                positions[i] = bounds.clamp(&(&positions[i] + &velocities[i]));
+                let value = result.evaluate(&positions[i], fitness);
+                if value > personal[i].1 {
+                    personal[i] = (positions[i].clone(), value);
+                }
+            }
+            result.history.push(result.fitness);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Negative shifted sphere, maximum 0 at (1, -2, 0.5)
+    fn sphere(x: &Array1<f64>) -> f64 {
+        let target = [1.0, -2.0, 0.5];
+        -x.iter().zip(target).map(|(v, t)| (v - t).powi(2)).sum::<f64>()
+    }
+
+    #[test]
+    fn test_optimizers_find_sphere_optimum() {
+        let bounds = Bounds::uniform(3, -5.0, 5.0).unwrap();
+        let optimizers: Vec<Box<dyn BlackBoxOptimizer>> = vec![
+            Box::new(CmaEs::new(0.3).with_seed(1)),
+            Box::new(DifferentialEvolution::new(20).with_seed(2)),
+            Box::new(ParticleSwarm::new(20).with_seed(3)),
+        ];
+        for mut optimizer in optimizers {
+            let mut calls = 0;
+            let mut fitness = |x: &Array1<f64>| {
+                calls += 1;
+                sphere(x)
+            };
+            let result = optimizer.optimize(&bounds, &mut fitness, 3000);
+            assert!(result.fitness > -1e-3, "{} reached {}", optimizer.name(), result.fitness);
+            assert!(result.evaluations <= 3000);
+            assert_eq!(result.evaluations, calls);
+            assert!(result.history.windows(2).all(|w| w[1] >= w[0]));
+        }
+    }
+
+    #[test]
+    fn test_bounds_are_respected() {
+        let bounds = Bounds::new(vec![2.0, -1.0], vec![3.0, 1.0]).unwrap();
+        assert!(Bounds::new(vec![1.0], vec![0.0]).is_err());
+        let mut outside = 0;
+        let mut fitness = |x: &Array1<f64>| {
+            if x[0] < 2.0 || x[0] > 3.0 || x[1].abs() > 1.0 {
+                outside += 1;
+            }
+            -x[0]
+        };
+        let result = CmaEs::new(0.5).with_seed(4).optimize(&bounds, &mut fitness, 400);
+        assert_eq!(outside, 0);
+        assert!((result.best[0] - 2.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,243 @@
+// Closed-loop control: PID, hysteresis and rate limiting
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+/// Whether the actuator moves with or against the error `setpoint − measurement`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlAction {
+    /// Output rises while the measurement is below the setpoint
+    Direct,
+    /// Output falls while the measurement is below the setpoint
+    Reverse,
+}
+
+/// PID controller in incremental (velocity) form
+///
+/// Each update returns a change of the actuator rather than its absolute value, so the
+/// actuator can be set externally between updates and clamping it cannot wind up the
+/// integral term.
+#[derive(Debug, Clone)]
+pub struct PidController {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+    previous_error: Option<f64>,
+    previous_delta: f64,
+}
+
+impl PidController {
+    pub fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        PidController {
+            kp,
+            ki,
+            kd,
+            previous_error: None,
+            previous_delta: 0.0,
+        }
+    }
+
+    /// Actuator change for the current error over a time step `dt`
+    pub fn update(&mut self, error: f64, dt: f64) -> f64 {
+        let dt = if dt > 0.0 { dt } else { 1.0 };
+        let previous = self.previous_error.unwrap_or(error);
+        let delta = error - previous;
+        let curvature = delta - self.previous_delta;
+        self.previous_error = Some(error);
+        self.previous_delta = delta;
+        self.kp * delta + self.ki * error * dt + self.kd * curvature / dt
+    }
+
+    pub fn reset(&mut self) {
+        self.previous_error = None;
+        self.previous_delta = 0.0;
+    }
+}
+
+/// Deadband with hysteresis: control engages once `|error|` exceeds `engage` and
+/// releases only after it falls below `release`
+#[derive(Debug, Clone)]
+pub struct Hysteresis {
+    pub engage: f64,
+    pub release: f64,
+    pub engaged: bool,
+}
+
+impl Hysteresis {
+    pub fn new(engage: f64, release: f64) -> Self {
+        Hysteresis {
+            engage: engage.max(release),
+            release: release.min(engage),
+            engaged: false,
+        }
+    }
+
+    pub fn update(&mut self, error: f64) -> bool {
+        let magnitude = error.abs();
+        if self.engaged {
+            self.engaged = magnitude >= self.release;
+        } else {
+            self.engaged = magnitude > self.engage;
+        }
+        self.engaged
+    }
+}
+
+/// One controller step
+#[derive(Debug, Clone)]
+pub struct ControlRecord {
+    pub step: usize,
+    pub setpoint: f64,
+    pub measurement: f64,
+    pub error: f64,
+    /// Actuator value after the step
+    pub output: f64,
+    pub engaged: bool,
+}
+
+/// PID loop driving a bounded actuator towards a bounded setpoint
+#[derive(Debug, Clone)]
+pub struct ControlLoop {
+    pub pid: PidController,
+    pub hysteresis: Hysteresis,
+    pub action: ControlAction,
+    setpoint: f64,
+    pub setpoint_bounds: (f64, f64),
+    pub output_bounds: (f64, f64),
+    /// Largest actuator change per step
+    pub rate_limit: f64,
+    /// Most recent steps, oldest first
+    pub history: VecDeque<ControlRecord>,
+    /// Records kept in `history`; older steps are dropped
+    pub history_limit: usize,
+    steps: usize,
+}
+
+impl ControlLoop {
+    pub fn new(pid: PidController, setpoint: f64, output_bounds: (f64, f64)) -> Self {
+        ControlLoop {
+            pid,
+            hysteresis: Hysteresis::new(0.0, 0.0),
+            action: ControlAction::Direct,
+            setpoint,
+            setpoint_bounds: (f64::NEG_INFINITY, f64::INFINITY),
+            output_bounds: (output_bounds.0.min(output_bounds.1), output_bounds.0.max(output_bounds.1)),
+            rate_limit: f64::INFINITY,
+            history: VecDeque::new(),
+            history_limit: 1024,
+            steps: 0,
+        }
+    }
+
+    pub fn setpoint(&self) -> f64 {
+        self.setpoint
+    }
+
+    /// Move the setpoint, clamped to `setpoint_bounds`; returns the value adopted
+    pub fn set_setpoint(&mut self, setpoint: f64) -> f64 {
+        self.setpoint = setpoint.clamp(self.setpoint_bounds.0, self.setpoint_bounds.1);
+        self.setpoint
+    }
+
+    /// New actuator value given its current value and a measurement
+    pub fn step(&mut self, output: f64, measurement: f64, dt: f64) -> f64 {
+        let error = match self.action {
+            ControlAction::Direct => self.setpoint - measurement,
+            ControlAction::Reverse => measurement - self.setpoint,
+        };
+        let engaged = self.hysteresis.update(error);
+        let next = if engaged {
+            let change = self.pid.update(error, dt).clamp(-self.rate_limit, self.rate_limit);
+            output + change
+        } else {
+            // Inside the band the actuator holds and the derivative history restarts
+            self.pid.reset();
+            output
+        };
+        let next = next.clamp(self.output_bounds.0, self.output_bounds.1);
+        self.history.push_back(ControlRecord {
+            step: self.steps,
+            setpoint: self.setpoint,
+            measurement,
+            error,
+            output: next,
+            engaged,
+        });
+        while self.history.len() > self.history_limit {
+            self.history.pop_front();
+        }
+        self.steps += 1;
+        next
+    }
+
+    /// Whether the last `window` outputs stayed within `tolerance` of each other
+    pub fn is_settled(&self, window: usize, tolerance: f64) -> bool {
+        if window == 0 || self.history.len() < window {
+            return false;
+        }
+        let recent = self.history.iter().skip(self.history.len() - window);
+        let (low, high) = recent.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), r| (lo.min(r.output), hi.max(r.output)));
+        high - low <= tolerance
+    }
+
+    /// Number of direction reversals of the actuator over `history`, a measure of thrashing
+    pub fn reversals(&self) -> usize {
+        let moves: Vec<f64> = self.history.iter().zip(self.history.iter().skip(1))
+            .map(|(a, b)| b.output - a.output)
+            .filter(|d| d.abs() > 1e-12)
+            .collect();
+        moves.windows(2).filter(|w| w[0].signum() != w[1].signum()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_settles_first_order_plant() {
+        // Plant: measurement relaxes towards twice the actuator value
+        let mut control = ControlLoop::new(PidController::new(0.3, 0.2, 0.0), 1.0, (0.0, 2.0));
+        control.rate_limit = 0.2;
+        let (mut output, mut measurement) = (0.0, 0.0);
+        for _ in 0..200 {
+            output = control.step(output, measurement, 1.0);
+            measurement += 0.5 * (2.0 * output - measurement);
+        }
+        assert!((measurement - 1.0).abs() < 1e-3);
+        assert!(control.is_settled(20, 1e-3));
+        assert!(control.history.iter().zip(control.history.iter().skip(1))
+            .all(|(a, b)| (b.output - a.output).abs() <= 0.2 + 1e-12));
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut control = ControlLoop::new(PidController::new(0.3, 0.2, 0.0), 1.0, (0.0, 2.0));
+        control.history_limit = 8;
+        let mut output = 0.0;
+        for _ in 0..50 {
+            output = control.step(output, 0.5, 1.0);
+        }
+        assert_eq!(control.history.len(), 8);
+        assert_eq!(control.history.front().unwrap().step, 42);
+        assert_eq!(control.history.back().unwrap().step, 49);
+    }
+
+    #[test]
+    fn test_hysteresis_and_bounds() {
+        let mut band = Hysteresis::new(0.5, 0.1);
+        assert!(!band.update(0.3));
+        assert!(band.update(0.6));
+        assert!(band.update(0.3));
+        assert!(!band.update(0.05));
+
+        let mut control = ControlLoop::new(PidController::new(1.0, 1.0, 0.0), 5.0, (0.0, 1.0));
+        control.setpoint_bounds = (0.0, 2.0);
+        assert_eq!(control.set_setpoint(5.0), 2.0);
+        control.action = ControlAction::Reverse;
+        // Measurement above the setpoint drives a reverse-acting output up to its bound
+        let output = (0..10).fold(0.5, |out, _| control.step(out, 3.0, 1.0));
+        assert_eq!(output, 1.0);
+        assert_eq!(control.reversals(), 0);
+    }
+}
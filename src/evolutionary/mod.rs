@@ -4,33 +4,60 @@
 use crate::core::tensor::MorphicTensor;
 use crate::field_stabilization::FieldStabilizer;
 use crate::quantum_classical::HybridComputation;
+use ndarray::Array1;
+use rand::Rng;
 
 pub struct AdaptiveLearning {
     pub base_learning_rate: f64,
     pub stability_threshold: f64,
+    /// Drives the energy ratio towards `stability_threshold` by moving the learning rate
+    pub controller: ControlLoop,
 }
 
 impl AdaptiveLearning {
     pub fn new(base_rate: f64, threshold: f64) -> Self {
-        AdaptiveLearning {
+        let mut controller = ControlLoop::new(
+            PidController::new(0.05 * base_rate, 0.02 * base_rate, 0.0),
+            threshold,
+            (base_rate * 0.1, base_rate * 2.0),
+        );
+        // A stable system (ratio below threshold) lowers the rate, an unstable one raises it
+        controller.action = ControlAction::Reverse;
+        controller.setpoint_bounds = (0.0, f64::INFINITY);
+        controller.hysteresis = Hysteresis::new(0.1 * threshold, 0.02 * threshold);
+        let mut adaptive = AdaptiveLearning {
             base_learning_rate: base_rate,
             stability_threshold: threshold,
-        }
+            controller,
+        };
+        adaptive.follow_base_rate();
+        adaptive
+    }
+
+    /// Scale the PID gains, actuator range and per-step limit to `base_learning_rate`
+    fn follow_base_rate(&mut self) {
+        let base = self.base_learning_rate;
+        self.controller.pid.kp = 0.05 * base;
+        self.controller.pid.ki = 0.02 * base;
+        self.controller.pid.kd = 0.0;
+        self.controller.output_bounds = (0.1 * base, 2.0 * base);
+        self.controller.rate_limit = 0.1 * base;
     }
 
     /// Dynamically adjust learning rate based on system stability
-    pub fn adjust_learning_rate(&self, stabilizer: &mut FieldStabilizer, energy_change: f64) {
+    ///
+    /// The rate stays within `[0.1, 2]·base_learning_rate` and moves at most
+    /// `0.1·base_learning_rate` per call, following any retuned base rate.
+    pub fn adjust_learning_rate(&mut self, stabilizer: &mut FieldStabilizer, energy_change: f64) {
+        self.follow_base_rate();
         let energy_ratio = energy_change.abs() / stabilizer.convergence_threshold;
+        self.stability_threshold = self.controller.set_setpoint(self.stability_threshold);
 
-        if energy_ratio < self.stability_threshold {
-            // System is stable - decrease learning rate
-            stabilizer.learning_rate = (stabilizer.learning_rate * 0.9)
-                .max(self.base_learning_rate * 0.1);
+        let previous = stabilizer.learning_rate;
+        stabilizer.learning_rate = self.controller.step(previous, energy_ratio, 1.0);
+        if stabilizer.learning_rate < previous {
             println!("Decreased learning rate to: {:.6}", stabilizer.learning_rate);
-        } else {
-            // System is unstable - increase learning rate
-            stabilizer.learning_rate = (stabilizer.learning_rate * 1.1)
-                .min(self.base_learning_rate * 2.0);
+        } else if stabilizer.learning_rate > previous {
             println!("Increased learning rate to: {:.6}", stabilizer.learning_rate);
         }
     }
@@ -67,28 +94,34 @@ impl AutoCalibration {
 pub struct FeedbackLoop {
     pub performance_threshold: f64,
     pub hybrid_computation: HybridComputation,
+    /// Drives performance towards `performance_threshold` by moving the quantum threshold
+    pub controller: ControlLoop,
 }
 
 impl FeedbackLoop {
     pub fn new(threshold: f64, hybrid: HybridComputation) -> Self {
+        let mut controller = ControlLoop::new(PidController::new(0.2, 0.1, 0.02), threshold, (0.0, 1.0));
+        // Underperformance lowers the quantum threshold so more work runs on the quantum path
+        controller.action = ControlAction::Reverse;
+        controller.setpoint_bounds = (0.0, 1.0);
+        controller.rate_limit = 0.05;
+        controller.hysteresis = Hysteresis::new(0.02, 0.005);
         FeedbackLoop {
             performance_threshold: threshold,
             hybrid_computation: hybrid,
+            controller,
         }
     }
 
     /// Apply performance-based feedback to quantum threshold
     pub fn apply_feedback(&mut self, performance: f64) {
-        if performance < self.performance_threshold {
-            // Decrease quantum threshold to use more quantum computation
-            self.hybrid_computation.quantum_threshold *= 0.95;
-            println!("Decreased quantum threshold to: {:.4}",
-                     self.hybrid_computation.quantum_threshold);
-        } else {
-            // Increase quantum threshold to use more classical computation
-            self.hybrid_computation.quantum_threshold *= 1.05;
-            println!("Increased quantum threshold to: {:.4}",
-                     self.hybrid_computation.quantum_threshold);
+        self.performance_threshold = self.controller.set_setpoint(self.performance_threshold);
+        let previous = self.hybrid_computation.quantum_threshold;
+        self.hybrid_computation.quantum_threshold = self.controller.step(previous, performance, 1.0);
+        if self.hybrid_computation.quantum_threshold < previous {
+            println!("Decreased quantum threshold to: {:.4}", self.hybrid_computation.quantum_threshold);
+        } else if self.hybrid_computation.quantum_threshold > previous {
+            println!("Increased quantum threshold to: {:.4}", self.hybrid_computation.quantum_threshold);
         }
     }
 }
@@ -129,4 +162,102 @@ impl EvolutionaryOptimizer {
 
         println!("Optimization cycle complete!");
     }
+
+    /// Search the stabilizer's learning rate for the lowest energy reached by gradient
+    /// descent on a copy of `tensor`, then adopt the best rate
+    pub fn tune_stabilizer(&mut self, optimizer: &mut dyn BlackBoxOptimizer, stabilizer: &mut FieldStabilizer,
+                           tensor: &MorphicTensor, rate_bounds: (f64, f64), budget: usize) -> Result<OptimizationResult, String> {
+        let bounds = Bounds::new(vec![rate_bounds.0], vec![rate_bounds.1])?;
+        let mut candidate = FieldStabilizer::new(stabilizer.learning_rate, stabilizer.convergence_threshold, stabilizer.max_iterations);
+        candidate.energy = stabilizer.energy.clone();
+        let mut fitness = |x: &Array1<f64>| {
+            candidate.learning_rate = x[0];
+            let mut trial = tensor.clone();
+            -candidate.gradient_descent(&mut trial).final_energy()
+        };
+        let result = optimizer.optimize(&bounds, &mut fitness, budget);
+        stabilizer.learning_rate = result.best[0];
+        self.learning_adapter.base_learning_rate = result.best[0];
+        println!("{} tuned learning rate to {:.6} (energy {:.6})", optimizer.name(), result.best[0], -result.fitness);
+        Ok(result)
+    }
+
+    /// Search the hybrid quantum threshold maximising a performance callback
+    pub fn tune_quantum_threshold(&mut self, optimizer: &mut dyn BlackBoxOptimizer, threshold_bounds: (f64, f64), budget: usize,
+                                  performance: &mut dyn FnMut(&HybridComputation) -> f64) -> Result<OptimizationResult, String> {
+        let bounds = Bounds::new(vec![threshold_bounds.0], vec![threshold_bounds.1])?;
+        let mut candidate = HybridComputation::new(self.feedback_loop.hybrid_computation.quantum_threshold);
+        candidate.classical_fallback = self.feedback_loop.hybrid_computation.classical_fallback;
+        let mut fitness = |x: &Array1<f64>| {
+            candidate.quantum_threshold = x[0];
+            performance(&candidate)
+        };
+        let result = optimizer.optimize(&bounds, &mut fitness, budget);
+        self.feedback_loop.hybrid_computation.quantum_threshold = result.best[0];
+        println!("{} tuned quantum threshold to {:.4} (performance {:.4})", optimizer.name(), result.best[0], result.fitness);
+        Ok(result)
+    }
+}
+
+/// Standard normal sample (Box–Muller)
+pub(crate) fn gaussian<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen::<f64>().max(f64::MIN_POSITIVE);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+pub mod blackbox;
+pub mod control;
+pub mod search;
+pub mod neuroevolution;
+
+pub use blackbox::{BlackBoxOptimizer, Bounds, CmaEs, DifferentialEvolution, OptimizationResult, ParticleSwarm};
+pub use control::{ControlAction, ControlLoop, ControlRecord, Hysteresis, PidController};
+pub use search::{Configuration, GaussianProcess, HyperparameterSearch, ParameterDomain, SearchSpace, SearchStrategy, Trial};
+pub use neuroevolution::{Genome, InnovationTracker, NeuronGene, Neuroevolution, NeuroevolutionStats, Species};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback_loop_converges_without_thrashing() {
+        let mut feedback = FeedbackLoop::new(0.8, HybridComputation::new(0.9));
+        // Performance improves as more work takes the quantum path
+        for _ in 0..200 {
+            let performance = 1.0 - feedback.hybrid_computation.quantum_threshold;
+            feedback.apply_feedback(performance);
+        }
+        let threshold = feedback.hybrid_computation.quantum_threshold;
+        assert!((threshold - 0.2).abs() <= 0.02 + 1e-9);
+        assert!(feedback.controller.is_settled(20, 1e-9));
+        assert!(feedback.controller.reversals() <= 2);
+
+        // Unreachable performance pins the threshold at its bound instead of drifting
+        for _ in 0..100 {
+            feedback.apply_feedback(0.0);
+        }
+        assert_eq!(feedback.hybrid_computation.quantum_threshold, 0.0);
+    }
+
+    #[test]
+    fn test_adjustment_follows_tuned_base_rate() {
+        let mut tensor = MorphicTensor::void();
+        tensor.potential.values = nalgebra::DVector::from_vec(vec![1.5, 0.8, 2.2, 1.0]);
+        let mut stabilizer = FieldStabilizer::new(0.1, 0.01, 50);
+        let mut optimizer = EvolutionaryOptimizer::new();
+
+        // Tuning lands well above the initial base rate's 2× ceiling of 0.2
+        let mut cma = CmaEs::new(0.3).with_seed(9);
+        optimizer.tune_stabilizer(&mut cma, &mut stabilizer, &tensor, (0.3, 0.5), 30).unwrap();
+        let tuned = stabilizer.learning_rate;
+        assert!(tuned >= 0.3);
+
+        // A large energy swing raises the rate by one step of the tuned rate limit
+        optimizer.learning_adapter.adjust_learning_rate(&mut stabilizer, 1.0);
+        assert!((stabilizer.learning_rate - 1.1 * tuned).abs() < 1e-12);
+        assert_eq!(optimizer.learning_adapter.controller.output_bounds, (0.1 * tuned, 2.0 * tuned));
+        let pid = &optimizer.learning_adapter.controller.pid;
+        assert_eq!((pid.kp, pid.ki, pid.kd), (0.05 * tuned, 0.02 * tuned, 0.0));
+    }
 }
@@ -0,0 +1,701 @@
+// NEAT-style neuroevolution of QNetwork topologies
+#![allow(dead_code)]
+
+use crate::environment::Environment;
+use crate::evolutionary::gaussian;
+use crate::learning::tensor_integration::QNetwork;
+use crate::learning::QNNLayer;
+use ndarray::{Array1, Array2};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A hidden or output neuron, identified across genomes by its innovation number
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeuronGene {
+    pub innovation: usize,
+    pub bias: f64,
+}
+
+/// Structural mutation, keyed so that identical mutations within a generation
+/// receive the same innovation number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Structure {
+    Neuron { layer: usize, slot: usize },
+    Layer { position: usize, slot: usize },
+}
+
+/// Hands out innovation numbers for new neurons
+///
+/// Inputs take innovations `0..inputs` and outputs `inputs..inputs + outputs`.
+#[derive(Debug, Clone)]
+pub struct InnovationTracker {
+    next: usize,
+    generation: HashMap<Structure, usize>,
+}
+
+impl InnovationTracker {
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        InnovationTracker {
+            next: inputs + outputs,
+            generation: HashMap::new(),
+        }
+    }
+
+    fn innovation(&mut self, structure: Structure) -> usize {
+        let next = &mut self.next;
+        *self.generation.entry(structure).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        })
+    }
+
+    /// Forget this generation's mutations so later ones get fresh numbers
+    pub fn new_generation(&mut self) {
+        self.generation.clear();
+    }
+}
+
+/// Layered network encoding: neurons carry innovation numbers and connections are
+/// keyed by the innovations of the neurons they join
+#[derive(Debug, Clone)]
+pub struct Genome {
+    pub inputs: usize,
+    /// Hidden layers followed by the output layer
+    pub layers: Vec<Vec<NeuronGene>>,
+    /// `(from, to)` innovation pairs between adjacent layers
+    pub connections: BTreeMap<(usize, usize), f64>,
+    pub fitness: f64,
+}
+
+impl Genome {
+    /// Random genome with the given hidden layer widths
+    pub fn new<R: Rng>(inputs: usize, hidden: &[usize], outputs: usize, tracker: &mut InnovationTracker, rng: &mut R) -> Self {
+        let mut layers: Vec<Vec<NeuronGene>> = hidden.iter()
+            .enumerate()
+            .map(|(layer, &width)| (0..width)
+                .map(|slot| NeuronGene { innovation: tracker.innovation(Structure::Neuron { layer, slot }), bias: 0.0 })
+                .collect())
+            .collect();
+        layers.push((0..outputs).map(|j| NeuronGene { innovation: inputs + j, bias: 0.0 }).collect());
+        let mut genome = Genome { inputs, layers, connections: BTreeMap::new(), fitness: 0.0 };
+        genome.connect(rng);
+        genome
+    }
+
+    /// Encode an existing network, numbering its hidden neurons by position
+    pub fn from_network(network: &QNetwork, tracker: &mut InnovationTracker) -> Self {
+        let sizes = network.layer_sizes();
+        let inputs = sizes[0];
+        let last = network.layers().len() - 1;
+        let mut genome = Genome { inputs, layers: Vec::new(), connections: BTreeMap::new(), fitness: 0.0 };
+        for (layer, qnn) in network.layers().iter().enumerate() {
+            let neurons: Vec<NeuronGene> = qnn.biases().iter()
+                .enumerate()
+                .map(|(slot, &bias)| {
+                    let innovation = if layer == last {
+                        inputs + slot
+                    } else {
+                        tracker.innovation(Structure::Neuron { layer, slot })
+                    };
+                    NeuronGene { innovation, bias }
+                })
+                .collect();
+            let sources = genome.sources(layer);
+            for (j, neuron) in neurons.iter().enumerate() {
+                for (i, &source) in sources.iter().enumerate() {
+                    genome.connections.insert((source, neuron.innovation), qnn.weights()[[j, i]]);
+                }
+            }
+            genome.layers.push(neurons);
+        }
+        genome
+    }
+
+    pub fn hidden_layers(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    pub fn hidden_neurons(&self) -> usize {
+        self.layers[..self.hidden_layers()].iter().map(Vec::len).sum()
+    }
+
+    /// Innovations feeding layer `layer`
+    fn sources(&self, layer: usize) -> Vec<usize> {
+        if layer == 0 {
+            (0..self.inputs).collect()
+        } else {
+            self.layers[layer - 1].iter().map(|n| n.innovation).collect()
+        }
+    }
+
+    /// Drop connections that no longer join adjacent layers and add missing ones
+    fn connect<R: Rng>(&mut self, rng: &mut R) {
+        let mut valid = HashSet::new();
+        for layer in 0..self.layers.len() {
+            let sources = self.sources(layer);
+            let scale = (2.0 / sources.len().max(1) as f64).sqrt();
+            for neuron in &self.layers[layer] {
+                for &source in &sources {
+                    valid.insert((source, neuron.innovation));
+                    self.connections.entry((source, neuron.innovation))
+                        .or_insert_with(|| scale * (2.0 * rng.gen::<f64>() - 1.0));
+                }
+            }
+        }
+        self.connections.retain(|key, _| valid.contains(key));
+    }
+
+    /// Decode into a dense network
+    pub fn to_network(&self, learning_rate: f64) -> QNetwork {
+        let layers = self.layers.iter()
+            .enumerate()
+            .map(|(layer, neurons)| {
+                let sources = self.sources(layer);
+                let weights = Array2::from_shape_fn((neurons.len(), sources.len()), |(j, i)| {
+                    self.connections.get(&(sources[i], neurons[j].innovation)).copied().unwrap_or(0.0)
+                });
+                let biases = Array1::from_iter(neurons.iter().map(|n| n.bias));
+                QNNLayer::from_parameters(weights, biases, learning_rate)
+            })
+            .collect();
+        QNetwork::from_layers(layers, learning_rate)
+    }
+
+    /// NEAT compatibility distance `c1·E/N + c2·D/N + c3·W̄`
+    ///
+    /// Neuron genes beyond the other genome's newest innovation are excess, all other
+    /// unmatched neuron and connection genes are disjoint.
+    pub fn distance(&self, other: &Genome, c1: f64, c2: f64, c3: f64) -> f64 {
+        let neurons = |g: &Genome| -> HashSet<usize> {
+            g.layers.iter().flatten().map(|n| n.innovation).collect()
+        };
+        let (ours, theirs) = (neurons(self), neurons(other));
+        let horizon = ours.iter().max().copied().unwrap_or(0).min(theirs.iter().max().copied().unwrap_or(0));
+        let (mut excess, mut disjoint) = (0usize, 0usize);
+        for &innovation in ours.symmetric_difference(&theirs) {
+            if innovation > horizon { excess += 1 } else { disjoint += 1 }
+        }
+
+        let (mut matching, mut weight_difference) = (0usize, 0.0);
+        for (key, weight) in &self.connections {
+            match other.connections.get(key) {
+                Some(w) => {
+                    matching += 1;
+                    weight_difference += (weight - w).abs();
+                }
+                None => disjoint += 1,
+            }
+        }
+        disjoint += other.connections.keys().filter(|k| !self.connections.contains_key(k)).count();
+
+        let genes = (ours.len() + self.connections.len()).max(theirs.len() + other.connections.len());
+        let n = if genes < 20 { 1.0 } else { genes as f64 };
+        let mean_difference = if matching == 0 { 0.0 } else { weight_difference / matching as f64 };
+        c1 * excess as f64 / n + c2 * disjoint as f64 / n + c3 * mean_difference
+    }
+
+    /// Child with the structure of `self` (the fitter parent); matching genes are
+    /// inherited from either parent at random
+    pub fn crossover<R: Rng>(&self, other: &Genome, rng: &mut R) -> Genome {
+        let biases: HashMap<usize, f64> = other.layers.iter().flatten().map(|n| (n.innovation, n.bias)).collect();
+        let mut child = self.clone();
+        for neuron in child.layers.iter_mut().flatten() {
+            if let Some(&bias) = biases.get(&neuron.innovation) {
+                if rng.gen::<bool>() {
+                    neuron.bias = bias;
+                }
+            }
+        }
+        for (key, weight) in child.connections.iter_mut() {
+            if let Some(&w) = other.connections.get(key) {
+                if rng.gen::<bool>() {
+                    *weight = w;
+                }
+            }
+        }
+        child.fitness = 0.0;
+        child
+    }
+
+    /// Insert a neuron into a hidden layer; its outgoing weights start at zero
+    pub fn add_neuron<R: Rng>(&mut self, tracker: &mut InnovationTracker, rng: &mut R) -> bool {
+        if self.hidden_layers() == 0 {
+            return self.add_layer(tracker, rng);
+        }
+        let layer = rng.gen_range(0..self.hidden_layers());
+        let slot = self.layers[layer].len();
+        let innovation = tracker.innovation(Structure::Neuron { layer, slot });
+        if self.layers[layer].iter().any(|n| n.innovation == innovation) {
+            return false;
+        }
+        self.layers[layer].push(NeuronGene { innovation, bias: 0.0 });
+        for target in self.layers[layer + 1].iter().map(|n| n.innovation).collect::<Vec<_>>() {
+            self.connections.insert((innovation, target), 0.0);
+        }
+        self.connect(rng);
+        true
+    }
+
+    /// Insert a hidden layer as wide as the layer it feeds
+    pub fn add_layer<R: Rng>(&mut self, tracker: &mut InnovationTracker, rng: &mut R) -> bool {
+        let position = rng.gen_range(0..=self.hidden_layers());
+        let width = self.layers[position].len().max(1);
+        let neurons: Vec<NeuronGene> = (0..width)
+            .map(|slot| NeuronGene { innovation: tracker.innovation(Structure::Layer { position, slot }), bias: 0.0 })
+            .collect();
+        if neurons.iter().any(|n| self.layers.iter().flatten().any(|m| m.innovation == n.innovation)) {
+            return false;
+        }
+        self.layers.insert(position, neurons);
+        self.connect(rng);
+        true
+    }
+
+    /// Remove a neuron from a hidden layer that keeps at least one other
+    pub fn remove_neuron<R: Rng>(&mut self, rng: &mut R) -> bool {
+        let candidates: Vec<usize> = (0..self.hidden_layers()).filter(|&l| self.layers[l].len() > 1).collect();
+        let Some(&layer) = candidates.choose(rng) else {
+            return false;
+        };
+        let index = rng.gen_range(0..self.layers[layer].len());
+        self.layers[layer].remove(index);
+        self.connect(rng);
+        true
+    }
+
+    /// Remove a whole hidden layer, reconnecting its neighbours
+    pub fn remove_layer<R: Rng>(&mut self, rng: &mut R) -> bool {
+        if self.hidden_layers() == 0 {
+            return false;
+        }
+        let layer = rng.gen_range(0..self.hidden_layers());
+        self.layers.remove(layer);
+        self.connect(rng);
+        true
+    }
+
+    /// Perturb each weight and bias with probability `rate`, occasionally replacing it
+    pub fn mutate_weights<R: Rng>(&mut self, rate: f64, power: f64, replace: f64, rng: &mut R) {
+        let mut perturb = |value: &mut f64| {
+            if rng.gen::<f64>() < rate {
+                if rng.gen::<f64>() < replace {
+                    *value = 2.0 * rng.gen::<f64>() - 1.0;
+                } else {
+                    *value += gaussian(rng) * power;
+                }
+            }
+        };
+        self.connections.values_mut().for_each(&mut perturb);
+        self.layers.iter_mut().flatten().for_each(|n| perturb(&mut n.bias));
+    }
+}
+
+/// Group of topologically similar genomes that compete mostly among themselves
+#[derive(Debug, Clone)]
+pub struct Species {
+    pub id: usize,
+    pub representative: Genome,
+    /// Indices into the population
+    pub members: Vec<usize>,
+    pub best_fitness: f64,
+    /// Generations without improving `best_fitness`
+    pub stagnation: usize,
+}
+
+/// Summary of one evaluated generation
+#[derive(Debug, Clone)]
+pub struct NeuroevolutionStats {
+    pub generation: usize,
+    pub best: f64,
+    pub mean: f64,
+    pub species: usize,
+    pub mean_hidden_layers: f64,
+    pub mean_hidden_neurons: f64,
+}
+
+/// Mean return of a network acting greedily over episodes of fresh environments
+pub fn environment_return<E, F>(network: &QNetwork, make_environment: &F, episodes: usize, max_steps: usize) -> f64
+where
+    E: Environment,
+    F: Fn() -> E,
+{
+    if episodes == 0 {
+        return 0.0;
+    }
+    let mut total = 0.0;
+    for _ in 0..episodes {
+        let mut environment = make_environment();
+        let mut state = environment.reset();
+        let actions = environment.action_space_size();
+        for _ in 0..max_steps {
+            let output = network.forward(&state);
+            let action = output.iter()
+                .take(actions)
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            let (next, reward, done) = environment.step(action);
+            total += reward;
+            state = next;
+            if done {
+                break;
+            }
+        }
+    }
+    total / episodes as f64
+}
+
+/// NEAT over `QNetwork` weights and layer structure
+pub struct Neuroevolution {
+    pub population: Vec<Genome>,
+    pub species: Vec<Species>,
+    pub tracker: InnovationTracker,
+    pub learning_rate: f64,
+    // Speciation
+    pub compatibility_threshold: f64,
+    pub excess_coefficient: f64,
+    pub disjoint_coefficient: f64,
+    pub weight_coefficient: f64,
+    pub stagnation_limit: usize,
+    // Reproduction
+    /// Fraction of each species allowed to breed
+    pub survival_threshold: f64,
+    pub crossover_rate: f64,
+    pub weight_mutation_rate: f64,
+    pub weight_perturbation: f64,
+    pub weight_replace_rate: f64,
+    pub add_neuron_rate: f64,
+    pub remove_neuron_rate: f64,
+    pub add_layer_rate: f64,
+    pub remove_layer_rate: f64,
+    pub generation: usize,
+    pub history: Vec<NeuroevolutionStats>,
+    pub best: Option<Genome>,
+    next_species: usize,
+    rng: StdRng,
+}
+
+impl Neuroevolution {
+    /// Population of random genomes sharing the same initial topology
+    pub fn new(inputs: usize, hidden: &[usize], outputs: usize, size: usize) -> Self {
+        Self::from_rng(inputs, hidden, outputs, size, StdRng::from_entropy())
+    }
+
+    /// `new` with initial weights and every later generation drawn from `seed`
+    pub fn new_seeded(inputs: usize, hidden: &[usize], outputs: usize, size: usize, seed: u64) -> Self {
+        Self::from_rng(inputs, hidden, outputs, size, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(inputs: usize, hidden: &[usize], outputs: usize, size: usize, mut rng: StdRng) -> Self {
+        let mut tracker = InnovationTracker::new(inputs, outputs);
+        let population = (0..size).map(|_| Genome::new(inputs, hidden, outputs, &mut tracker, &mut rng)).collect();
+        Self::from_population(population, tracker, rng)
+    }
+
+    /// Population of weight-perturbed copies of an existing network
+    pub fn from_network(network: &QNetwork, size: usize) -> Self {
+        Self::from_network_rng(network, size, StdRng::from_entropy())
+    }
+
+    /// `from_network` with the perturbations and every later generation drawn from `seed`
+    pub fn from_network_seeded(network: &QNetwork, size: usize, seed: u64) -> Self {
+        Self::from_network_rng(network, size, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_network_rng(network: &QNetwork, size: usize, mut rng: StdRng) -> Self {
+        let sizes = network.layer_sizes();
+        let mut tracker = InnovationTracker::new(sizes[0], sizes[sizes.len() - 1]);
+        let seed = Genome::from_network(network, &mut tracker);
+        let population = (0..size)
+            .map(|i| {
+                let mut genome = seed.clone();
+                if i > 0 {
+                    genome.mutate_weights(1.0, 0.1, 0.0, &mut rng);
+                }
+                genome
+            })
+            .collect();
+        let mut neat = Self::from_population(population, tracker, rng);
+        neat.learning_rate = network.learning_rate();
+        neat
+    }
+
+    fn from_population(population: Vec<Genome>, tracker: InnovationTracker, rng: StdRng) -> Self {
+        Neuroevolution {
+            population,
+            species: Vec::new(),
+            tracker,
+            learning_rate: 0.01,
+            compatibility_threshold: 3.0,
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 1.0,
+            weight_coefficient: 0.4,
+            stagnation_limit: 15,
+            survival_threshold: 0.3,
+            crossover_rate: 0.75,
+            weight_mutation_rate: 0.8,
+            weight_perturbation: 0.3,
+            weight_replace_rate: 0.1,
+            add_neuron_rate: 0.05,
+            remove_neuron_rate: 0.03,
+            add_layer_rate: 0.02,
+            remove_layer_rate: 0.01,
+            generation: 0,
+            history: Vec::new(),
+            best: None,
+            next_species: 0,
+            rng,
+        }
+    }
+
+    /// Evaluate, speciate and breed one generation
+    pub fn step(&mut self, fitness: &dyn Fn(&QNetwork) -> f64) -> NeuroevolutionStats {
+        for genome in &mut self.population {
+            genome.fitness = fitness(&genome.to_network(self.learning_rate));
+        }
+        // Non-finite scores rank below every finite one
+        let floor = self.population.iter().map(|g| g.fitness).filter(|f| f.is_finite()).fold(0.0, f64::min) - 1.0;
+        for genome in &mut self.population {
+            if !genome.fitness.is_finite() {
+                genome.fitness = floor;
+            }
+        }
+        if let Some(champion) = self.population.iter().max_by(|a, b| a.fitness.total_cmp(&b.fitness)) {
+            if self.best.as_ref().is_none_or(|best| champion.fitness > best.fitness) {
+                self.best = Some(champion.clone());
+            }
+        }
+
+        self.speciate();
+        let stats = self.statistics();
+        println!("Neuroevolution generation {}: best {:.4}, mean {:.4}, {} species",
+                 stats.generation, stats.best, stats.mean, stats.species);
+        self.history.push(stats.clone());
+
+        self.reproduce();
+        self.generation += 1;
+        stats
+    }
+
+    /// Run `generations` generations
+    pub fn run(&mut self, generations: usize, fitness: &dyn Fn(&QNetwork) -> f64) -> &[NeuroevolutionStats] {
+        let start = self.history.len();
+        for _ in 0..generations {
+            self.step(fitness);
+        }
+        &self.history[start..]
+    }
+
+    /// Run generations scored by the mean greedy return in fresh environments
+    pub fn evolve_in<E, F>(&mut self, generations: usize, make_environment: F, episodes: usize, max_steps: usize) -> &[NeuroevolutionStats]
+    where
+        E: Environment,
+        F: Fn() -> E,
+    {
+        self.run(generations, &|network: &QNetwork| environment_return(network, &make_environment, episodes, max_steps))
+    }
+
+    /// Best genome found so far, decoded
+    pub fn best_network(&self) -> Option<QNetwork> {
+        self.best.as_ref().map(|g| g.to_network(self.learning_rate))
+    }
+
+    /// Assign every genome to the first compatible species
+    fn speciate(&mut self) {
+        for species in &mut self.species {
+            species.members.clear();
+        }
+        for (index, genome) in self.population.iter().enumerate() {
+            let home = self.species.iter().position(|s| {
+                genome.distance(&s.representative, self.excess_coefficient, self.disjoint_coefficient, self.weight_coefficient)
+                    < self.compatibility_threshold
+            });
+            match home {
+                Some(s) => self.species[s].members.push(index),
+                None => {
+                    self.species.push(Species {
+                        id: self.next_species,
+                        representative: genome.clone(),
+                        members: vec![index],
+                        best_fitness: f64::NEG_INFINITY,
+                        stagnation: 0,
+                    });
+                    self.next_species += 1;
+                }
+            }
+        }
+        self.species.retain(|s| !s.members.is_empty());
+
+        let champion = self.population.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.fitness.total_cmp(&b.1.fitness))
+            .map(|(i, _)| i);
+        for species in &mut self.species {
+            let best = species.members.iter().map(|&i| self.population[i].fitness).fold(f64::NEG_INFINITY, f64::max);
+            if best > species.best_fitness {
+                species.best_fitness = best;
+                species.stagnation = 0;
+            } else {
+                species.stagnation += 1;
+            }
+        }
+        // Stagnant species die out, except the one holding the champion
+        let limit = self.stagnation_limit;
+        self.species.retain(|s| s.stagnation <= limit || champion.is_some_and(|c| s.members.contains(&c)));
+    }
+
+    /// Offspring per species, proportional to summed fitness shared within the species
+    fn allocate(&self) -> Vec<usize> {
+        let size = self.population.len();
+        let minimum = self.population.iter().map(|g| g.fitness).fold(f64::INFINITY, f64::min);
+        let shares: Vec<f64> = self.species.iter()
+            .map(|s| s.members.iter().map(|&i| self.population[i].fitness - minimum + 1e-6).sum::<f64>() / s.members.len() as f64)
+            .collect();
+        let total: f64 = shares.iter().sum();
+        let exact: Vec<f64> = shares.iter().map(|s| s / total * size as f64).collect();
+        let mut counts: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+
+        // Largest remainders take the leftover slots
+        let mut order: Vec<usize> = (0..counts.len()).collect();
+        order.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+        let assigned: usize = counts.iter().sum();
+        for &s in order.iter().cycle().take(size.saturating_sub(assigned)) {
+            counts[s] += 1;
+        }
+        counts
+    }
+
+    fn reproduce(&mut self) {
+        self.tracker.new_generation();
+        let counts = self.allocate();
+        let rng = &mut self.rng;
+        let mut next = Vec::with_capacity(self.population.len());
+
+        for (species, &count) in self.species.iter_mut().zip(&counts) {
+            let mut members = species.members.clone();
+            members.sort_by(|&a, &b| self.population[b].fitness.total_cmp(&self.population[a].fitness));
+            species.representative = self.population[*members.choose(rng).expect("species are not empty")].clone();
+            if count == 0 {
+                continue;
+            }
+            // The species champion survives unchanged
+            next.push(self.population[members[0]].clone());
+
+            let breeders = ((members.len() as f64 * self.survival_threshold).ceil() as usize).clamp(1, members.len());
+            for _ in 1..count {
+                let a = &self.population[members[rng.gen_range(0..breeders)]];
+                let b = &self.population[members[rng.gen_range(0..breeders)]];
+                let mut child = if rng.gen::<f64>() < self.crossover_rate {
+                    if a.fitness >= b.fitness { a.crossover(b, rng) } else { b.crossover(a, rng) }
+                } else {
+                    a.clone()
+                };
+
+                if rng.gen::<f64>() < self.add_layer_rate {
+                    child.add_layer(&mut self.tracker, rng);
+                }
+                if rng.gen::<f64>() < self.add_neuron_rate {
+                    child.add_neuron(&mut self.tracker, rng);
+                }
+                if rng.gen::<f64>() < self.remove_neuron_rate {
+                    child.remove_neuron(rng);
+                }
+                if rng.gen::<f64>() < self.remove_layer_rate {
+                    child.remove_layer(rng);
+                }
+                child.mutate_weights(self.weight_mutation_rate, self.weight_perturbation, self.weight_replace_rate, rng);
+                next.push(child);
+            }
+        }
+        self.population = next;
+    }
+
+    fn statistics(&self) -> NeuroevolutionStats {
+        let n = self.population.len().max(1) as f64;
+        NeuroevolutionStats {
+            generation: self.generation,
+            best: self.population.iter().map(|g| g.fitness).fold(f64::NEG_INFINITY, f64::max),
+            mean: self.population.iter().map(|g| g.fitness).sum::<f64>() / n,
+            species: self.species.len(),
+            mean_hidden_layers: self.population.iter().map(|g| g.hidden_layers() as f64).sum::<f64>() / n,
+            mean_hidden_neurons: self.population.iter().map(|g| g.hidden_neurons() as f64).sum::<f64>() / n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::TestEnvironment;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_structural_mutations_keep_network_valid() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut tracker = InnovationTracker::new(3, 2);
+        let mut genome = Genome::new(3, &[4], 2, &mut tracker, &mut rng);
+        let input = Array1::from_vec(vec![0.2, -0.1, 0.4]);
+        let before = genome.to_network(0.01).forward(&input);
+
+        // New neurons start with zero outgoing weights, so behaviour is unchanged
+        assert!(genome.add_neuron(&mut tracker, &mut rng));
+        assert_eq!(genome.to_network(0.01).layer_sizes(), vec![3, 5, 2]);
+        let after = genome.to_network(0.01).forward(&input);
+        assert!((before - after).iter().all(|d| d.abs() < 1e-12));
+
+        assert!(genome.add_layer(&mut tracker, &mut rng));
+        assert_eq!(genome.hidden_layers(), 2);
+        assert!(genome.remove_neuron(&mut rng));
+        assert!(genome.remove_layer(&mut rng));
+        let network = genome.to_network(0.01);
+        assert_eq!(network.layer_sizes().first(), Some(&3));
+        assert_eq!(network.output_dim(), 2);
+        assert_eq!(genome.connections.len(),
+                   network.layers().iter().map(|l| l.weights().len()).sum::<usize>());
+
+        // Round trip through a QNetwork preserves the function
+        let mut fresh = InnovationTracker::new(3, 2);
+        let decoded = Genome::from_network(&network, &mut fresh).to_network(0.01);
+        assert!((network.forward(&input) - decoded.forward(&input)).iter().all(|d| d.abs() < 1e-12));
+
+        // Seeded populations from the same network are reproducible
+        let a = Neuroevolution::from_network_seeded(&network, 5, 3);
+        let b = Neuroevolution::from_network_seeded(&network, 5, 3);
+        assert!(a.population.iter().zip(&b.population).all(|(x, y)| x.connections == y.connections));
+    }
+
+    #[test]
+    fn test_distance_and_crossover() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut tracker = InnovationTracker::new(2, 1);
+        let a = Genome::new(2, &[3], 1, &mut tracker, &mut rng);
+        let mut b = a.clone();
+        assert_eq!(a.distance(&b, 1.0, 1.0, 0.4), 0.0);
+
+        tracker.new_generation();
+        b.add_neuron(&mut tracker, &mut rng);
+        assert!(a.distance(&b, 1.0, 1.0, 0.4) > 0.0);
+
+        // The child takes the fitter parent's topology
+        let child = b.crossover(&a, &mut rng);
+        assert_eq!(child.hidden_neurons(), 4);
+        assert_eq!(a.crossover(&b, &mut rng).hidden_neurons(), 3);
+    }
+
+    #[test]
+    fn test_neuroevolution_improves_environment_return() {
+        let make = || TestEnvironment::new(4, 10);
+        let mut neat = Neuroevolution::new_seeded(4, &[3], 4, 30, 5);
+        let stats = neat.evolve_in(15, make, 1, 10).to_vec();
+        assert_eq!(stats.len(), 15);
+        assert_eq!(neat.population.len(), 30);
+        // Always taking action 0 earns 0.9 per step
+        let best = neat.best_network().unwrap();
+        assert!(environment_return(&best, &make, 1, 10) > 8.0);
+        assert!(stats.last().unwrap().best >= stats[0].best);
+    }
+}
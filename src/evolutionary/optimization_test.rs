@@ -1,5 +1,8 @@
 // Evolutionary Optimization Test
-use morph::evolutionary::EvolutionaryOptimizer;
+use morph::evolutionary::{BlackBoxOptimizer, Bounds, CmaEs, DifferentialEvolution, EvolutionaryOptimizer, Neuroevolution, ParticleSwarm};
+use morph::environment::{Environment, TestEnvironment};
+use morph::evolutionary::{HyperparameterSearch, ParameterDomain, SearchSpace, SearchStrategy};
+use morph::learning::QRLAgent;
 use morph::core::tensor::MorphicTensor;
 use morph::field_stabilization::FieldStabilizer;
 use nalgebra::DVector;
@@ -33,6 +36,84 @@ fn main() {
     println!("Final learning rate: {:.6}", stabilizer.learning_rate);
     println!("Final quantum threshold: {:.4}",
              optimizer.feedback_loop.hybrid_computation.quantum_threshold);
+    for record in &optimizer.feedback_loop.controller.history {
+        println!("  feedback step {}: performance {:.3}, error {:+.3}, threshold {:.4}{}",
+                 record.step, record.measurement, record.error, record.output,
+                 if record.engaged { "" } else { " (held)" });
+    }
+    println!("Learning-rate reversals: {}", optimizer.learning_adapter.controller.reversals());
+
+    // Black-box tuning of system parameters
+    println!("\n--- Black-box tuning ---");
+    let mut cma = CmaEs::new(0.3);
+    if let Err(e) = optimizer.tune_stabilizer(&mut cma, &mut stabilizer, &tensor, (0.001, 0.5), 60) {
+        println!("Stabilizer tuning failed: {}", e);
+    }
+    let mut de = DifferentialEvolution::new(8);
+    let mut performance = |hybrid: &morph::quantum_classical::HybridComputation| -(hybrid.quantum_threshold - 0.65).powi(2);
+    if let Err(e) = optimizer.tune_quantum_threshold(&mut de, (0.1, 1.0), 80, &mut performance) {
+        println!("Threshold tuning failed: {}", e);
+    }
+
+    // Variational angles of a two-qubit product ansatz, E = cos θ₁ + cos θ₂ + ½ sin θ₁ sin θ₂
+    let angles = Bounds::uniform(2, 0.0, std::f64::consts::TAU).unwrap();
+    let mut energy = |theta: &ndarray::Array1<f64>| {
+        -(theta[0].cos() + theta[1].cos() + 0.5 * theta[0].sin() * theta[1].sin())
+    };
+    let mut swarm = ParticleSwarm::new(12);
+    let result = swarm.optimize(&angles, &mut energy, 600);
+    println!("{}: angles {:?}, energy {:.4} after {} evaluations",
+             swarm.name(), result.best.to_vec(), -result.fitness, result.evaluations);
+
+    // Sweep QRL agent hyperparameters with successive halving
+    println!("\n--- Hyperparameter search ---");
+    let mut space = SearchSpace::new();
+    space.add("learning_rate", ParameterDomain::LogUniform { low: 1e-4, high: 0.5 }).unwrap()
+        .add("gamma", ParameterDomain::Continuous { low: 0.8, high: 0.999 }).unwrap();
+    let agent_return = |c: &morph::evolutionary::Configuration, episodes: usize| {
+        let mut agent = QRLAgent::new(4, 4, c["learning_rate"], c["gamma"]);
+        let mut last = 0.0;
+        for _ in 0..episodes {
+            let mut environment = TestEnvironment::new(4, 10);
+            let mut state = environment.reset();
+            let (mut states, mut actions, mut rewards) = (Vec::new(), Vec::new(), Vec::new());
+            for _ in 0..10 {
+                let action = agent.select_action(&state);
+                let (next, reward, done) = environment.step(action);
+                states.extend(state.iter().cloned());
+                actions.push(action);
+                rewards.push(reward);
+                state = next;
+                if done {
+                    break;
+                }
+            }
+            let states = ndarray::Array2::from_shape_vec((actions.len(), 4), states).unwrap();
+            agent.update_policy(&states, &actions, &rewards);
+            last = rewards.iter().sum();
+        }
+        last
+    };
+    let mut search = HyperparameterSearch::new(space, SearchStrategy::SuccessiveHalving { configurations: 9, min_budget: 2, eta: 3 }, 18);
+    search.run(&agent_return);
+    println!("{}", search.results_table());
+    let path = std::env::temp_dir().join("morph_hyperparameters.csv");
+    match search.write_results(path.to_str().unwrap()) {
+        Ok(()) => println!("Results written to {}", path.display()),
+        Err(e) => println!("{}", e),
+    }
+
+    // Evolve agent network topologies against a test environment
+    println!("\n--- Neuroevolution ---");
+    let mut neat = Neuroevolution::new(4, &[4], 4, 24);
+    neat.add_layer_rate = 0.1;
+    for stats in neat.evolve_in(8, || TestEnvironment::new(4, 10), 1, 10).to_vec() {
+        println!("  gen {}: best {:.3}, {} species, {:.1} hidden layers, {:.1} hidden neurons",
+                 stats.generation, stats.best, stats.species, stats.mean_hidden_layers, stats.mean_hidden_neurons);
+    }
+    if let Some(network) = neat.best_network() {
+        println!("Best topology: {:?}", network.layer_sizes());
+    }
 
     println!("✅ Evolutionary optimization tests completed!");
 }
@@ -0,0 +1,471 @@
+// Hyperparameter search over MORPH components
+#![allow(dead_code)]
+
+use nalgebra::{DMatrix, DVector};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Parameter values by name
+pub type Configuration = BTreeMap<String, f64>;
+
+/// Values a hyperparameter may take
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterDomain {
+    Continuous { low: f64, high: f64 },
+    /// Sampled uniformly in `ln` space; both ends must be positive
+    LogUniform { low: f64, high: f64 },
+    Integer { low: i64, high: i64 },
+    Choice(Vec<f64>),
+}
+
+impl ParameterDomain {
+    /// Map `u ∈ [0, 1]` into the domain
+    fn decode(&self, u: f64) -> f64 {
+        let u = u.clamp(0.0, 1.0);
+        match self {
+            ParameterDomain::Continuous { low, high } => low + u * (high - low),
+            ParameterDomain::LogUniform { low, high } => (low.ln() + u * (high.ln() - low.ln())).exp(),
+            ParameterDomain::Integer { low, high } => {
+                let span = (high - low + 1) as f64;
+                (*low + ((u * span).floor() as i64).min(high - low)) as f64
+            }
+            ParameterDomain::Choice(options) => {
+                options[((u * options.len() as f64).floor() as usize).min(options.len() - 1)]
+            }
+        }
+    }
+
+    /// Position of `value` in the unit interval
+    fn encode(&self, value: f64) -> f64 {
+        let ratio = |x: f64, low: f64, high: f64| if high > low { (x - low) / (high - low) } else { 0.5 };
+        match self {
+            ParameterDomain::Continuous { low, high } => ratio(value, *low, *high),
+            ParameterDomain::LogUniform { low, high } => ratio(value.ln(), low.ln(), high.ln()),
+            ParameterDomain::Integer { low, high } => ratio(value + 0.5, *low as f64, (*high + 1) as f64),
+            ParameterDomain::Choice(options) => {
+                let index = options.iter().position(|&o| o == value).unwrap_or(0);
+                (index as f64 + 0.5) / options.len() as f64
+            }
+        }
+    }
+
+    /// At most `points` evenly spread values
+    fn grid(&self, points: usize) -> Vec<f64> {
+        let points = points.max(1);
+        let mut values: Vec<f64> = match self {
+            ParameterDomain::Choice(options) => options.clone(),
+            ParameterDomain::Integer { low, high } if ((high - low + 1) as usize) <= points => {
+                (*low..=*high).map(|v| v as f64).collect()
+            }
+            _ if points == 1 => vec![self.decode(0.5)],
+            ParameterDomain::Integer { low, high } => (0..points)
+                .map(|i| (*low as f64 + (high - low) as f64 * i as f64 / (points - 1) as f64).round())
+                .collect(),
+            _ => (0..points).map(|i| self.decode(i as f64 / (points - 1) as f64)).collect(),
+        };
+        values.dedup();
+        values
+    }
+}
+
+/// Named hyperparameters and their domains
+#[derive(Debug, Clone, Default)]
+pub struct SearchSpace {
+    pub parameters: Vec<(String, ParameterDomain)>,
+}
+
+impl SearchSpace {
+    pub fn new() -> Self {
+        SearchSpace { parameters: Vec::new() }
+    }
+
+    pub fn add(&mut self, name: &str, domain: ParameterDomain) -> Result<&mut Self, String> {
+        let valid = match &domain {
+            ParameterDomain::Continuous { low, high } => low.is_finite() && high.is_finite() && low <= high,
+            ParameterDomain::LogUniform { low, high } => *low > 0.0 && high.is_finite() && low <= high,
+            ParameterDomain::Integer { low, high } => low <= high,
+            ParameterDomain::Choice(options) => !options.is_empty(),
+        };
+        if !valid {
+            return Err(format!("Invalid domain for parameter '{}': {:?}", name, domain));
+        }
+        if self.parameters.iter().any(|(n, _)| n == name) {
+            return Err(format!("Parameter '{}' is already defined", name));
+        }
+        self.parameters.push((name.to_string(), domain));
+        Ok(self)
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.parameters.len()
+    }
+
+    fn decode(&self, unit: &[f64]) -> Configuration {
+        self.parameters.iter()
+            .zip(unit)
+            .map(|((name, domain), &u)| (name.clone(), domain.decode(u)))
+            .collect()
+    }
+
+    fn encode(&self, configuration: &Configuration) -> Vec<f64> {
+        self.parameters.iter()
+            .map(|(name, domain)| configuration.get(name).map_or(0.5, |&v| domain.encode(v)))
+            .collect()
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Configuration {
+        let unit: Vec<f64> = (0..self.dimension()).map(|_| rng.gen()).collect();
+        self.decode(&unit)
+    }
+
+    /// Cartesian product of each parameter's grid values
+    pub fn grid(&self, points: usize) -> Vec<Configuration> {
+        self.parameters.iter().fold(vec![Configuration::new()], |partial, (name, domain)| {
+            let values = domain.grid(points);
+            partial.iter()
+                .flat_map(|configuration| values.iter().map(move |&v| {
+                    let mut next = configuration.clone();
+                    next.insert(name.clone(), v);
+                    next
+                }))
+                .collect()
+        })
+    }
+}
+
+/// How configurations are proposed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchStrategy {
+    /// Every combination of `points` values per parameter
+    Grid { points: usize },
+    Random { trials: usize },
+    /// Gaussian-process surrogate with expected improvement, proposing `batch`
+    /// configurations per round after `initial` random trials
+    Bayesian { initial: usize, iterations: usize, batch: usize },
+    /// Evaluate `configurations` random configurations at `min_budget`, keep the best
+    /// `1/eta`, multiply the budget by `eta` and repeat
+    SuccessiveHalving { configurations: usize, min_budget: usize, eta: usize },
+}
+
+/// One evaluated configuration
+#[derive(Debug, Clone)]
+pub struct Trial {
+    pub id: usize,
+    pub configuration: Configuration,
+    /// Objective value; larger is better
+    pub score: f64,
+    /// Budget the objective was given (e.g. episodes or iterations)
+    pub budget: usize,
+    /// Successive halving rung or Bayesian round, zero otherwise
+    pub round: usize,
+    pub duration: Duration,
+}
+
+/// Standard normal CDF (Abramowitz–Stegun 7.1.26 approximation of erf)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Gaussian-process regression with a squared-exponential kernel on the unit cube
+pub struct GaussianProcess {
+    points: Vec<Vec<f64>>,
+    alpha: DVector<f64>,
+    cholesky: nalgebra::Cholesky<f64, nalgebra::Dyn>,
+    mean: f64,
+    scale: f64,
+    pub length_scale: f64,
+}
+
+impl GaussianProcess {
+    /// Fit to observations, standardising the targets; `None` without data
+    pub fn fit(points: &[Vec<f64>], values: &[f64], length_scale: f64, noise: f64) -> Option<Self> {
+        if points.is_empty() || points.len() != values.len() {
+            return None;
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let scale = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt().max(1e-12);
+        let kernel = DMatrix::from_fn(points.len(), points.len(), |i, j| {
+            Self::kernel(&points[i], &points[j], length_scale) + if i == j { noise.max(1e-10) } else { 0.0 }
+        });
+        let cholesky = kernel.cholesky()?;
+        let targets = DVector::from_iterator(values.len(), values.iter().map(|v| (v - mean) / scale));
+        let alpha = cholesky.solve(&targets);
+        Some(GaussianProcess { points: points.to_vec(), alpha, cholesky, mean, scale, length_scale })
+    }
+
+    fn kernel(a: &[f64], b: &[f64], length_scale: f64) -> f64 {
+        let distance: f64 = a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum();
+        (-distance / (2.0 * length_scale * length_scale)).exp()
+    }
+
+    /// Posterior mean and standard deviation at `x`
+    pub fn predict(&self, x: &[f64]) -> (f64, f64) {
+        let k = DVector::from_iterator(self.points.len(), self.points.iter().map(|p| Self::kernel(p, x, self.length_scale)));
+        let mean = k.dot(&self.alpha);
+        let v = self.cholesky.l().solve_lower_triangular(&k).unwrap_or_else(|| DVector::zeros(k.len()));
+        let variance = (1.0 - v.dot(&v)).max(1e-12);
+        (self.mean + mean * self.scale, variance.sqrt() * self.scale)
+    }
+
+    /// Expected improvement over `best` when maximising
+    pub fn expected_improvement(&self, x: &[f64], best: f64) -> f64 {
+        let (mean, deviation) = self.predict(x);
+        let gain = mean - best - 0.01 * self.scale;
+        let z = gain / deviation;
+        gain * normal_cdf(z) + deviation * normal_pdf(z)
+    }
+}
+
+/// Runs trials of an objective over a search space
+pub struct HyperparameterSearch {
+    pub space: SearchSpace,
+    pub strategy: SearchStrategy,
+    /// Budget handed to the objective outside successive halving
+    pub max_budget: usize,
+    pub trials: Vec<Trial>,
+    rng: StdRng,
+}
+
+impl HyperparameterSearch {
+    pub fn new(space: SearchSpace, strategy: SearchStrategy, max_budget: usize) -> Self {
+        HyperparameterSearch {
+            space,
+            strategy,
+            max_budget,
+            trials: Vec::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Make sampling reproducible (the objective itself may still be random)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Run the strategy, maximising `objective(configuration, budget)`
+    pub fn run<F>(&mut self, objective: &F) -> &[Trial]
+    where
+        F: Fn(&Configuration, usize) -> f64 + Sync,
+    {
+        let start = self.trials.len();
+        println!("Hyperparameter search over {} parameters ({:?})", self.space.dimension(), self.strategy);
+        match self.strategy {
+            SearchStrategy::Grid { points } => {
+                let configurations = self.space.grid(points);
+                self.evaluate(configurations, self.max_budget, 0, objective);
+            }
+            SearchStrategy::Random { trials } => {
+                let configurations = (0..trials).map(|_| self.space.sample(&mut self.rng)).collect();
+                self.evaluate(configurations, self.max_budget, 0, objective);
+            }
+            SearchStrategy::Bayesian { initial, iterations, batch } => {
+                let configurations = (0..initial.max(1)).map(|_| self.space.sample(&mut self.rng)).collect();
+                self.evaluate(configurations, self.max_budget, 0, objective);
+                for round in 1..=iterations {
+                    let proposals = self.propose(batch.max(1), start);
+                    self.evaluate(proposals, self.max_budget, round, objective);
+                }
+            }
+            SearchStrategy::SuccessiveHalving { configurations, min_budget, eta } => {
+                let eta = eta.max(2);
+                let mut survivors: Vec<Configuration> = (0..configurations).map(|_| self.space.sample(&mut self.rng)).collect();
+                let mut budget = min_budget.max(1);
+                let mut rung = 0;
+                while !survivors.is_empty() {
+                    let first = self.trials.len();
+                    self.evaluate(survivors, budget, rung, objective);
+                    let mut ranked: Vec<&Trial> = self.trials[first..].iter().collect();
+                    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+                    let keep = ranked.len() / eta;
+                    if keep == 0 || budget.saturating_mul(eta) > self.max_budget.max(min_budget) {
+                        break;
+                    }
+                    survivors = ranked[..keep].iter().map(|t| t.configuration.clone()).collect();
+                    budget *= eta;
+                    rung += 1;
+                }
+            }
+        }
+        if let Some(best) = self.best() {
+            println!("Best score {:.6} with {:?}", best.score, best.configuration);
+        }
+        &self.trials[start..]
+    }
+
+    /// Evaluate configurations in parallel
+    fn evaluate<F>(&mut self, configurations: Vec<Configuration>, budget: usize, round: usize, objective: &F)
+    where
+        F: Fn(&Configuration, usize) -> f64 + Sync,
+    {
+        let first = self.trials.len();
+        let results: Vec<(Configuration, f64, Duration)> = configurations.into_par_iter()
+            .map(|configuration| {
+                let started = Instant::now();
+                let score = objective(&configuration, budget);
+                let score = if score.is_nan() { f64::NEG_INFINITY } else { score };
+                (configuration, score, started.elapsed())
+            })
+            .collect();
+        self.trials.extend(results.into_iter().enumerate().map(|(i, (configuration, score, duration))| Trial {
+            id: first + i,
+            configuration,
+            score,
+            budget,
+            round,
+            duration,
+        }));
+    }
+
+    /// Batch of configurations maximising expected improvement, using the surrogate's
+    /// own prediction as a stand-in result for earlier picks in the batch
+    fn propose(&mut self, batch: usize, since: usize) -> Vec<Configuration> {
+        let observed: Vec<&Trial> = self.trials[since..].iter().filter(|t| t.score.is_finite()).collect();
+        let mut points: Vec<Vec<f64>> = observed.iter().map(|t| self.space.encode(&t.configuration)).collect();
+        let mut values: Vec<f64> = observed.iter().map(|t| t.score).collect();
+        let length_scale = 0.2 * (self.space.dimension().max(1) as f64).sqrt();
+        let mut proposals = Vec::with_capacity(batch);
+
+        for _ in 0..batch {
+            let candidates: Vec<Vec<f64>> = (0..512)
+                .map(|_| (0..self.space.dimension()).map(|_| self.rng.gen()).collect())
+                .collect();
+            let Some(surrogate) = GaussianProcess::fit(&points, &values, length_scale, 1e-6) else {
+                proposals.push(self.space.decode(&candidates[0]));
+                continue;
+            };
+            let best = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let chosen = candidates.into_iter()
+                .map(|c| (surrogate.expected_improvement(&c, best), c))
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, c)| c)
+                .expect("candidates are not empty");
+            // Snap to the domain so the stand-in sits where the trial will run
+            let configuration = self.space.decode(&chosen);
+            let snapped = self.space.encode(&configuration);
+            values.push(surrogate.predict(&snapped).0);
+            points.push(snapped);
+            proposals.push(configuration);
+        }
+        proposals
+    }
+
+    /// Highest-scoring trial, preferring larger budgets on ties
+    pub fn best(&self) -> Option<&Trial> {
+        self.trials.iter().max_by(|a, b| a.score.total_cmp(&b.score).then(a.budget.cmp(&b.budget)))
+    }
+
+    /// Trials as an aligned text table, best first
+    pub fn results_table(&self) -> String {
+        let names: Vec<&str> = self.space.parameters.iter().map(|(n, _)| n.as_str()).collect();
+        let mut rows: Vec<Vec<String>> = vec![
+            ["trial", "round", "budget", "score", "ms"].iter().map(|s| s.to_string())
+                .chain(names.iter().map(|s| s.to_string()))
+                .collect(),
+        ];
+        let mut ranked: Vec<&Trial> = self.trials.iter().collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        for trial in ranked {
+            let mut row = vec![
+                trial.id.to_string(),
+                trial.round.to_string(),
+                trial.budget.to_string(),
+                format!("{:.6}", trial.score),
+                format!("{:.1}", trial.duration.as_secs_f64() * 1000.0),
+            ];
+            row.extend(names.iter().map(|n| trial.configuration.get(*n).map_or(String::new(), |v| format!("{:.6}", v))));
+            rows.push(row);
+        }
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|c| rows.iter().map(|r| r[c].len()).max().unwrap_or(0))
+            .collect();
+        rows.iter()
+            .map(|row| row.iter().zip(&widths).map(|(cell, w)| format!("{:>w$}", cell, w = w)).collect::<Vec<_>>().join("  "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Write the trials as CSV
+    pub fn write_results(&self, path: &str) -> Result<(), String> {
+        let names: Vec<&str> = self.space.parameters.iter().map(|(n, _)| n.as_str()).collect();
+        let mut csv = format!("trial,round,budget,score,duration_ms,{}\n", names.join(","));
+        for trial in &self.trials {
+            let values: Vec<String> = names.iter()
+                .map(|n| trial.configuration.get(*n).map_or(String::new(), |v| v.to_string()))
+                .collect();
+            csv.push_str(&format!("{},{},{},{},{},{}\n", trial.id, trial.round, trial.budget, trial.score,
+                                  trial.duration.as_secs_f64() * 1000.0, values.join(",")));
+        }
+        std::fs::write(path, csv).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn space() -> SearchSpace {
+        let mut space = SearchSpace::new();
+        space.add("rate", ParameterDomain::LogUniform { low: 1e-4, high: 1.0 }).unwrap()
+            .add("pressure", ParameterDomain::Continuous { low: 0.0, high: 1.0 }).unwrap()
+            .add("layers", ParameterDomain::Integer { low: 1, high: 4 }).unwrap();
+        space
+    }
+
+    /// Peaks at rate = 0.01, pressure = 0.7, layers = 2
+    fn objective(c: &Configuration, _budget: usize) -> f64 {
+        -(c["rate"].log10() + 2.0).powi(2) - 4.0 * (c["pressure"] - 0.7).powi(2) - 0.5 * (c["layers"] - 2.0).abs()
+    }
+
+    #[test]
+    fn test_grid_and_random_search() {
+        let mut grid = HyperparameterSearch::new(space(), SearchStrategy::Grid { points: 5 }, 1);
+        assert_eq!(grid.run(&objective).len(), 5 * 5 * 4);
+        assert_eq!(grid.best().unwrap().configuration["layers"], 2.0);
+        assert!(grid.results_table().lines().count() == 101);
+
+        let mut random = HyperparameterSearch::new(space(), SearchStrategy::Random { trials: 40 }, 1).with_seed(1);
+        random.run(&objective);
+        assert!(random.trials.iter().all(|t| (1e-4..=1.0).contains(&t.configuration["rate"])));
+        assert!(space().add("rate", ParameterDomain::Choice(vec![1.0])).is_err());
+    }
+
+    #[test]
+    fn test_bayesian_beats_initial_design() {
+        let mut search = HyperparameterSearch::new(space(), SearchStrategy::Bayesian { initial: 8, iterations: 10, batch: 3 }, 1)
+            .with_seed(4);
+        search.run(&objective);
+        assert_eq!(search.trials.len(), 8 + 30);
+        let initial = search.trials[..8].iter().map(|t| t.score).fold(f64::NEG_INFINITY, f64::max);
+        assert!(search.best().unwrap().score > initial);
+        assert!(search.best().unwrap().score > -0.3);
+
+        let gp = GaussianProcess::fit(&[vec![0.0], vec![1.0]], &[1.0, 3.0], 0.5, 1e-8).unwrap();
+        let (mean, deviation) = gp.predict(&[1.0]);
+        assert!((mean - 3.0).abs() < 1e-3 && deviation < 1e-2);
+    }
+
+    #[test]
+    fn test_successive_halving_promotes_best() {
+        // Noisy at small budgets, exact at large ones
+        let noisy = |c: &Configuration, budget: usize| objective(c, budget) - 1.0 / budget as f64;
+        let mut search = HyperparameterSearch::new(space(), SearchStrategy::SuccessiveHalving { configurations: 27, min_budget: 1, eta: 3 }, 27)
+            .with_seed(2);
+        search.run(&noisy);
+        let rungs: Vec<usize> = (0..4).map(|r| search.trials.iter().filter(|t| t.round == r).count()).collect();
+        assert_eq!(rungs, vec![27, 9, 3, 1]);
+        assert_eq!(search.best().unwrap().budget, 27);
+    }
+}
@@ -1,22 +1,68 @@
 // Field Stabilization Test
-use morph::field_stabilization::FieldStabilizer;
+use morph::field_stabilization::{FieldStabilizer, EnergyFunctional, StabilizationIntegrator};
+use morph::field_stabilization::{FieldLattice, FieldSolver, Reaction, TimeStepping};
 use morph::core::tensor::MorphicTensor;
 use nalgebra::DVector;
+use ndarray::array;
 
 fn main() {
     println!("Testing Field Stabilization...");
 
-    // Create test tensor with potential field
-    let mut tensor = MorphicTensor::void();
+    // Create test tensor with potential field and spatial data
+    let mut tensor = MorphicTensor::from_data(array![0.4, -1.1, 2.3]);
     tensor.potential.values = DVector::from_vec(vec![2.5, 1.8, 3.2]);
+    tensor.entanglement.strength = 0.8;
 
     // Create stabilizer
-    let stabilizer = FieldStabilizer::new(0.1, 0.01, 100);
+    let mut stabilizer = FieldStabilizer::new(0.1, 0.01, 100);
+    stabilizer.energy = EnergyFunctional::new(1.0, 0.5, 0.8);
 
     // Run stabilization
-    stabilizer.gradient_descent(&mut tensor);
+    let initial_energy = stabilizer.developmental_energy(&tensor);
+    let trace = stabilizer.gradient_descent(&mut tensor);
     let energy = stabilizer.developmental_energy(&tensor);
 
+    println!("Initial energy: {:.4}", initial_energy);
     println!("Developmental energy: {:.4}", energy);
+    println!("Iterations: {} (converged: {})", trace.iterations(), trace.converged);
+    println!("Energy trace (first 5): {:?}",
+             trace.energies.iter().take(5).map(|e| format!("{:.4}", e)).collect::<Vec<_>>());
+    assert!(energy < initial_energy);
+
+    // Run the full stabilization pipeline on a rugged potential
+    let mut rugged = MorphicTensor::from_data(array![1.0, 0.5, 1.5, 0.8, 1.2]);
+    rugged.potential.values = DVector::from_vec(vec![3.0, -1.0, 2.5, -0.5, 2.0]);
+    rugged.entanglement.strength = 0.6;
+    println!("Initial persistence diagram: {:?}",
+             stabilizer.persistence_diagram(&rugged));
+
+    let mut integrator = StabilizationIntegrator::new(FieldStabilizer::new(0.1, 0.01, 200));
+    let reports = integrator.integrate_until_converged(&mut rugged, 5);
+    let last = reports.last().unwrap();
+    println!("Pipeline passes: {}", reports.len());
+    println!("Coherence after projection: {:?}", last.coherence);
+    println!("Energy history: {:?}",
+             integrator.stabilizer.energy_history.iter().map(|e| format!("{:.4}", e)).collect::<Vec<_>>());
+    assert!(last.final_energy <= reports[0].initial_energy);
+
+    // Reaction–diffusion over a population of tensors on a Delaunay mesh
+    let mut population: Vec<MorphicTensor> = (0..12)
+        .map(|k| {
+            let angle = k as f64 * 0.9;
+            let mut t = MorphicTensor::void();
+            t.spatial.coordinates = [angle.cos() * (1.0 + k as f64 * 0.2), angle.sin() * (1.0 + k as f64 * 0.2)];
+            t.potential.values = DVector::from_vec(vec![if k < 3 { 0.9 } else { 0.05 }]);
+            t
+        })
+        .collect();
+    let mesh = FieldLattice::delaunay(&population).expect("distinct coordinates");
+    let mut solver = FieldSolver::new(0.5, 0.5, TimeStepping::Implicit);
+    solver.reaction = Reaction::Logistic { rate: 1.0, capacity: 1.0 };
+    let changes = solver.solve(&mesh, &mut population, 40).expect("implicit solve");
+    println!("Mesh nodes: {}, stable explicit dt: {:.4}", mesh.len(), solver.stable_explicit_dt(&mesh));
+    println!("Final step change: {:.6}", changes.last().unwrap());
+    println!("Field after reaction–diffusion: {:?}",
+             population.iter().map(|t| format!("{:.3}", t.potential.values[0])).collect::<Vec<_>>());
+
     println!("✅ Field stabilization tests completed!");
 }
@@ -2,7 +2,18 @@
 #![allow(dead_code)]
 
 use crate::core::tensor::MorphicTensor;
-use crate::field_stabilization::FieldStabilizer;
+use crate::field_stabilization::{ConvergenceTrace, FieldStabilizer};
+
+/// Outcome of one pass through the stabilization pipeline
+#[derive(Debug, Clone)]
+pub struct StabilizationReport {
+    pub initial_energy: f64,
+    pub final_energy: f64,
+    pub trace: ConvergenceTrace,
+    /// Potential/data coherence after projection, if the tensor carries data
+    pub coherence: Option<f64>,
+    pub topologically_converged: bool,
+}
 
 pub struct StabilizationIntegrator {
     pub stabilizer: FieldStabilizer,
@@ -13,24 +24,47 @@ impl StabilizationIntegrator {
         StabilizationIntegrator { stabilizer }
     }
 
-    pub fn integrate(&self, tensor: &mut MorphicTensor) {
+    pub fn integrate(&mut self, tensor: &mut MorphicTensor) -> StabilizationReport {
         println!("Integrating field stabilization...");
 
         // Monitor initial energy
-        self.stabilizer.monitor_energy(tensor);
+        let initial_energy = self.stabilizer.monitor_energy(tensor);
 
         // Apply gradient descent
-        self.stabilizer.gradient_descent(tensor);
+        let trace = self.stabilizer.gradient_descent(tensor);
 
         // Preserve entanglement
-        self.stabilizer.preserve_entanglement_coherence(tensor);
+        let coherence = self.stabilizer.preserve_entanglement_coherence(tensor);
 
         // Check convergence
-        if self.stabilizer.topological_convergence(tensor) {
+        let topologically_converged = self.stabilizer.topological_convergence(tensor);
+        if topologically_converged {
             println!("Topological convergence achieved!");
         }
 
         // Monitor final energy
-        self.stabilizer.monitor_energy(tensor);
+        let final_energy = self.stabilizer.monitor_energy(tensor);
+
+        StabilizationReport {
+            initial_energy,
+            final_energy,
+            trace,
+            coherence,
+            topologically_converged,
+        }
+    }
+
+    /// Run the pipeline until topological convergence or `max_passes` passes
+    pub fn integrate_until_converged(&mut self, tensor: &mut MorphicTensor, max_passes: usize) -> Vec<StabilizationReport> {
+        let mut reports = Vec::new();
+        for _ in 0..max_passes {
+            let report = self.integrate(tensor);
+            let converged = report.topologically_converged && report.trace.converged;
+            reports.push(report);
+            if converged {
+                break;
+            }
+        }
+        reports
     }
 }
@@ -3,10 +3,46 @@
 
 use crate::core::tensor::MorphicTensor;
 
+pub mod energy;
+pub mod topology;
+pub mod integration;
+pub mod lattice;
+
+pub use energy::{EnergyFunctional, EnergyGradient};
+pub use topology::{sublevel_persistence, PersistencePair};
+pub use integration::{StabilizationIntegrator, StabilizationReport};
+pub use lattice::{FieldLattice, FieldSolver, Reaction, TimeStepping};
+
 pub struct FieldStabilizer {
     pub learning_rate: f64,
     pub convergence_threshold: f64,
     pub max_iterations: usize,
+    pub energy: EnergyFunctional,
+    pub energy_history: Vec<f64>,
+}
+
+/// Per-iteration record of a gradient descent run
+#[derive(Debug, Clone)]
+pub struct ConvergenceTrace {
+    /// Energy before the first step followed by the energy after each step
+    pub energies: Vec<f64>,
+    /// Gradient norm observed at the start of each iteration
+    pub gradient_norms: Vec<f64>,
+    pub converged: bool,
+}
+
+impl ConvergenceTrace {
+    pub fn iterations(&self) -> usize {
+        self.gradient_norms.len()
+    }
+
+    pub fn initial_energy(&self) -> f64 {
+        self.energies.first().copied().unwrap_or(0.0)
+    }
+
+    pub fn final_energy(&self) -> f64 {
+        self.energies.last().copied().unwrap_or(0.0)
+    }
 }
 
 impl FieldStabilizer {
@@ -15,16 +51,147 @@ impl FieldStabilizer {
             learning_rate,
             convergence_threshold: threshold,
             max_iterations: max_iters,
+            energy: EnergyFunctional::default(),
+            energy_history: Vec::new(),
         }
     }
 
-    /// Morphic gradient descent implementation
-    pub fn gradient_descent(&self, _tensor: &mut MorphicTensor) {
-        println!("Applying morphic gradient descent (placeholder)");
+    /// Morphic gradient descent on the developmental energy
+    ///
+    /// Updates the tensor's potential and spatial data in place until either the
+    /// gradient norm or the per-step energy change falls below `convergence_threshold`,
+    /// or `max_iterations` steps have been taken.
+    pub fn gradient_descent(&self, tensor: &mut MorphicTensor) -> ConvergenceTrace {
+        let mut trace = ConvergenceTrace {
+            energies: vec![self.developmental_energy(tensor)],
+            gradient_norms: Vec::new(),
+            converged: false,
+        };
+
+        for _ in 0..self.max_iterations {
+            let gradient = self.energy.gradient(tensor);
+            let gradient_norm = gradient.norm();
+            trace.gradient_norms.push(gradient_norm);
+
+            if gradient_norm < self.convergence_threshold {
+                trace.converged = true;
+                break;
+            }
+
+            tensor.potential.values.axpy(-self.learning_rate, &gradient.potential, 1.0);
+            if let (Some(data), Some(data_grad)) = (&mut tensor.spatial.data, &gradient.data) {
+                data.scaled_add(-self.learning_rate, data_grad);
+            }
+
+            let previous = trace.final_energy();
+            let energy = self.developmental_energy(tensor);
+            trace.energies.push(energy);
+
+            if (previous - energy).abs() < self.convergence_threshold {
+                trace.converged = true;
+                break;
+            }
+        }
+
+        println!("Morphic gradient descent: {} iterations, energy {:.4} → {:.4}{}",
+                 trace.iterations(), trace.initial_energy(), trace.final_energy(),
+                 if trace.converged { " (converged)" } else { "" });
+
+        trace
     }
 
     /// Calculate developmental energy
     pub fn developmental_energy(&self, tensor: &MorphicTensor) -> f64 {
-        tensor.potential.values.norm()
+        self.energy.energy(tensor)
+    }
+
+    /// Record the current developmental energy in the stabilizer's history
+    pub fn monitor_energy(&mut self, tensor: &MorphicTensor) -> f64 {
+        let energy = self.developmental_energy(tensor);
+        if let Some(previous) = self.energy_history.last() {
+            println!("Developmental energy: {:.4} (Δ {:+.4})", energy, energy - previous);
+        } else {
+            println!("Developmental energy: {:.4}", energy);
+        }
+        self.energy_history.push(energy);
+        energy
+    }
+
+    /// Cosine similarity between the potential and the spatial data over their overlap
+    pub fn entanglement_coherence(&self, tensor: &MorphicTensor) -> Option<f64> {
+        let data = tensor.spatial.data.as_ref()?;
+        let potential = &tensor.potential.values;
+        let overlap = potential.len().min(data.len());
+
+        let mut dot = 0.0;
+        let mut potential_sq = 0.0;
+        let mut data_sq = 0.0;
+        for i in 0..overlap {
+            dot += potential[i] * data[i];
+            potential_sq += potential[i] * potential[i];
+            data_sq += data[i] * data[i];
+        }
+
+        if potential_sq < f64::EPSILON || data_sq < f64::EPSILON {
+            return None;
+        }
+        Some(dot / (potential_sq.sqrt() * data_sq.sqrt()))
+    }
+
+    /// Project the potential back onto the entanglement coherence cone
+    ///
+    /// The feasible set is every potential whose coherence with the spatial data is at
+    /// least the tensor's entanglement strength. Potentials outside it are replaced by
+    /// their Euclidean projection onto the cone boundary; entries beyond the data length
+    /// are left untouched. Returns the coherence after projection.
+    pub fn preserve_entanglement_coherence(&self, tensor: &mut MorphicTensor) -> Option<f64> {
+        let coherence = self.entanglement_coherence(tensor)?;
+        let target = tensor.entanglement.strength.clamp(0.0, 1.0);
+        if coherence >= target {
+            return Some(coherence);
+        }
+
+        let data = tensor.spatial.data.as_ref()?;
+        let potential = &mut tensor.potential.values;
+        let overlap = potential.len().min(data.len());
+
+        // Orthonormal basis of the plane spanned by the data direction and the potential
+        let data_norm = (0..overlap).map(|i| data[i] * data[i]).sum::<f64>().sqrt();
+        let unit: Vec<f64> = (0..overlap).map(|i| data[i] / data_norm).collect();
+        let along: f64 = (0..overlap).map(|i| potential[i] * unit[i]).sum();
+        let perp: Vec<f64> = (0..overlap).map(|i| potential[i] - along * unit[i]).collect();
+        let perp_norm = perp.iter().map(|p| p * p).sum::<f64>().sqrt();
+        let potential_norm = (along * along + perp_norm * perp_norm).sqrt();
+
+        let theta = perp_norm.atan2(along);
+        let alpha = target.acos();
+        let length = potential_norm * (theta - alpha).cos();
+
+        for i in 0..overlap {
+            potential[i] = if length <= 0.0 || perp_norm < f64::EPSILON {
+                0.0
+            } else {
+                length * (alpha.cos() * unit[i] + alpha.sin() * perp[i] / perp_norm)
+            };
+        }
+
+        println!("Projected potential onto coherence cone: {:.4} → {:.4}", coherence, target);
+        Some(self.entanglement_coherence(tensor).unwrap_or(0.0))
+    }
+
+    /// Sublevel-set persistence diagram of the tensor's potential field
+    pub fn persistence_diagram(&self, tensor: &MorphicTensor) -> Vec<PersistencePair> {
+        sublevel_persistence(tensor.potential.values.as_slice())
+    }
+
+    /// Whether the potential has settled into a single basin
+    ///
+    /// Converged once every non-essential feature of the persistence diagram has
+    /// persistence below `convergence_threshold`, i.e. only noise-level local minima remain.
+    pub fn topological_convergence(&self, tensor: &MorphicTensor) -> bool {
+        self.persistence_diagram(tensor)
+            .iter()
+            .filter(|pair| !pair.is_essential())
+            .all(|pair| pair.persistence() < self.convergence_threshold)
     }
 }
@@ -0,0 +1,97 @@
+// Persistent homology of one-dimensional morphic fields
+#![allow(dead_code)]
+
+/// A 0-dimensional feature (basin) of the sublevel-set filtration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PersistencePair {
+    pub birth: f64,
+    /// `f64::INFINITY` for the essential class that never merges
+    pub death: f64,
+}
+
+impl PersistencePair {
+    pub fn persistence(&self) -> f64 {
+        self.death - self.birth
+    }
+
+    pub fn is_essential(&self) -> bool {
+        self.death.is_infinite()
+    }
+}
+
+/// Computes the 0-dimensional persistence diagram of a field sampled along a path
+///
+/// Entries are added in increasing order of value; when two basins meet, the younger
+/// one (higher birth) dies at the merging value (elder rule). Zero-persistence pairs
+/// are omitted, and the global minimum yields a single essential pair.
+pub fn sublevel_persistence(values: &[f64]) -> Vec<PersistencePair> {
+    let n = values.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut added = vec![false; n];
+    let mut pairs = Vec::new();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for &idx in &order {
+        added[idx] = true;
+        let neighbours = [idx.checked_sub(1), Some(idx + 1).filter(|&j| j < n)];
+
+        for neighbour in neighbours.into_iter().flatten() {
+            if !added[neighbour] {
+                continue;
+            }
+            let a = find(&mut parent, idx);
+            let b = find(&mut parent, neighbour);
+            if a == b {
+                continue;
+            }
+
+            // Roots are always their component's minimum, so compare their values
+            let (elder, younger) = if values[a] <= values[b] { (a, b) } else { (b, a) };
+            let pair = PersistencePair { birth: values[younger], death: values[idx] };
+            if pair.persistence() > 0.0 {
+                pairs.push(pair);
+            }
+            parent[younger] = elder;
+        }
+    }
+
+    pairs.push(PersistencePair { birth: values[order[0]], death: f64::INFINITY });
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_basins() {
+        // Minima at 0.0 and 1.0 separated by a ridge at 3.0
+        let pairs = sublevel_persistence(&[0.0, 2.0, 3.0, 1.0, 4.0]);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0], PersistencePair { birth: 1.0, death: 3.0 });
+        assert!(pairs[1].is_essential());
+        assert_eq!(pairs[1].birth, 0.0);
+    }
+
+    #[test]
+    fn test_monotone_field_has_single_class() {
+        let pairs = sublevel_persistence(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(pairs.len(), 1);
+        assert!(pairs[0].is_essential());
+        assert!(sublevel_persistence(&[]).is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+// Memory consolidation and hippocampal replay
+#![allow(dead_code)]
+
+use crate::core::tensor::MorphicTensor;
+use crate::hippocampus::index::CognitiveMap;
+use crate::hippocampus::memory::QuantumMemoryManager;
+use crate::learning::tensor_integration::{QNetwork, Trainable};
+use crate::learning::QRLAgent;
+use ndarray::{Array1, Array2};
+
+/// Ordering used when choosing which memories to replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayPriority {
+    /// Most recently recorded first
+    Recency,
+    /// Largest reward magnitude first
+    Reward,
+    /// Most novel first; novelty decays with every replay
+    Novelty,
+}
+
+/// A state/action/reward sample fed to a learner
+#[derive(Debug, Clone)]
+pub struct Experience {
+    pub state: Array1<f64>,
+    pub action: usize,
+    pub reward: f64,
+}
+
+/// Learners that can be trained from a batch of experiences
+pub trait ReplayTarget {
+    fn replay(&mut self, states: &Array2<f64>, actions: &[usize], rewards: &[f64]);
+}
+
+impl ReplayTarget for QRLAgent {
+    fn replay(&mut self, states: &Array2<f64>, actions: &[usize], rewards: &[f64]) {
+        self.update_policy(states, actions, rewards);
+    }
+}
+
+impl ReplayTarget for QNetwork {
+    /// Regress the Q-value of each taken action towards its reward
+    fn replay(&mut self, states: &Array2<f64>, actions: &[usize], rewards: &[f64]) {
+        let mut targets = self.predict(states);
+        for (i, (&action, &reward)) in actions.iter().zip(rewards).enumerate() {
+            if action < targets.ncols() {
+                targets[(i, action)] = reward;
+            }
+        }
+        self.train_step(states, &targets);
+    }
+}
+
+/// Bookkeeping for one short-term memory awaiting consolidation
+#[derive(Debug, Clone)]
+pub struct ConsolidationTrace {
+    pub memory_id: usize,
+    pub action: usize,
+    pub reward: f64,
+    pub recorded_at: u64,
+    /// Distance to the closest memory recorded before it
+    pub novelty: f64,
+    pub replays: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConsolidationReport {
+    /// Short-term memory IDs replayed this cycle
+    pub replayed: Vec<usize>,
+    /// `(memory id, cognitive map id)` of patterns moved to long-term storage
+    pub migrated: Vec<(usize, usize)>,
+    /// Short-term memories that had been evicted before they could be replayed
+    pub forgotten: Vec<usize>,
+}
+
+/// Replays short-term memories into a learner and migrates stable ones to the cognitive map
+///
+/// Every `consolidation_interval` ticks, the `replay_batch` highest-priority memories are
+/// interleaved with the fresh experience of that tick into one training batch. Memories
+/// replayed `stability_threshold` times are considered stable: they leave short-term
+/// memory and are inserted into the cognitive map.
+pub struct MemoryConsolidator {
+    pub memory: QuantumMemoryManager,
+    pub priority: ReplayPriority,
+    pub replay_batch: usize,
+    pub consolidation_interval: usize,
+    pub stability_threshold: usize,
+    traces: Vec<ConsolidationTrace>,
+    clock: u64,
+}
+
+impl MemoryConsolidator {
+    pub fn new(memory: QuantumMemoryManager, priority: ReplayPriority) -> Self {
+        MemoryConsolidator {
+            memory,
+            priority,
+            replay_batch: 8,
+            consolidation_interval: 10,
+            stability_threshold: 3,
+            traces: Vec::new(),
+            clock: 0,
+        }
+    }
+
+    /// Store a tensor in short-term memory together with the action and reward it led to
+    pub fn record(&mut self, tensor: &MorphicTensor, action: usize, reward: f64) -> usize {
+        let data = tensor.to_array();
+        let novelty = self.traces.iter()
+            .filter_map(|trace| self.memory.peek(trace.memory_id))
+            .map(|other| other.to_array())
+            .filter(|other| other.len() == data.len())
+            .map(|other| (&other - &data).mapv(|d| d * d).sum().sqrt())
+            .fold(f64::INFINITY, f64::min);
+
+        let memory_id = self.memory.store(tensor);
+        self.traces.push(ConsolidationTrace {
+            memory_id,
+            action,
+            reward,
+            recorded_at: self.clock,
+            novelty: if novelty.is_finite() { novelty } else { 1.0 },
+            replays: 0,
+        });
+        memory_id
+    }
+
+    pub fn traces(&self) -> &[ConsolidationTrace] {
+        &self.traces
+    }
+
+    /// Advance the scheduler; consolidates when the interval has elapsed
+    pub fn tick<L: ReplayTarget>(&mut self, learner: &mut L, map: &mut CognitiveMap, fresh: &[Experience]) -> Option<ConsolidationReport> {
+        self.clock += 1;
+        let interval = self.consolidation_interval.max(1) as u64;
+        if self.clock.is_multiple_of(interval) {
+            Some(self.consolidate(learner, map, fresh))
+        } else {
+            None
+        }
+    }
+
+    /// Memory IDs in replay order under the current priority
+    pub fn replay_order(&self) -> Vec<usize> {
+        let score = |trace: &ConsolidationTrace| match self.priority {
+            ReplayPriority::Recency => trace.recorded_at as f64,
+            ReplayPriority::Reward => trace.reward.abs(),
+            ReplayPriority::Novelty => trace.novelty / (1.0 + trace.replays as f64),
+        };
+        let mut ranked: Vec<&ConsolidationTrace> = self.traces.iter().collect();
+        // Stable sort keeps insertion order among equal scores
+        ranked.sort_by(|a, b| score(b).total_cmp(&score(a)));
+        ranked.into_iter().map(|trace| trace.memory_id).collect()
+    }
+
+    /// Run one replay and migration cycle immediately
+    pub fn consolidate<L: ReplayTarget>(&mut self, learner: &mut L, map: &mut CognitiveMap, fresh: &[Experience]) -> ConsolidationReport {
+        let mut report = ConsolidationReport::default();
+
+        // Drop traces whose memories were evicted from short-term storage
+        let memory = &self.memory;
+        self.traces.retain(|trace| {
+            let alive = memory.peek(trace.memory_id).is_some();
+            if !alive {
+                report.forgotten.push(trace.memory_id);
+            }
+            alive
+        });
+
+        let dimension = fresh.first()
+            .map(|e| e.state.len())
+            .or_else(|| self.traces.first()
+                .and_then(|t| self.memory.peek(t.memory_id))
+                .map(|t| t.to_array().len()));
+
+        let mut replayed: Vec<Experience> = Vec::new();
+        for memory_id in self.replay_order().into_iter().take(self.replay_batch) {
+            let Some(tensor) = self.memory.retrieve(memory_id) else {
+                continue;
+            };
+            let state = tensor.to_array();
+            if Some(state.len()) != dimension {
+                continue;
+            }
+            let trace = self.traces.iter_mut()
+                .find(|t| t.memory_id == memory_id)
+                .expect("replay order is drawn from traces");
+            trace.replays += 1;
+            replayed.push(Experience { state, action: trace.action, reward: trace.reward });
+            report.replayed.push(memory_id);
+        }
+
+        let batch = interleave(fresh, &replayed);
+        if let (Some(dim), false) = (dimension, batch.is_empty()) {
+            let mut states = Array2::zeros((batch.len(), dim));
+            for (i, experience) in batch.iter().enumerate() {
+                states.row_mut(i).assign(&experience.state);
+            }
+            let actions: Vec<usize> = batch.iter().map(|e| e.action).collect();
+            let rewards: Vec<f64> = batch.iter().map(|e| e.reward).collect();
+            learner.replay(&states, &actions, &rewards);
+        }
+
+        let threshold = self.stability_threshold;
+        let (stable, pending): (Vec<_>, Vec<_>) = self.traces.drain(..)
+            .partition(|trace| trace.replays >= threshold);
+        self.traces = pending;
+        for trace in stable {
+            if let Some(tensor) = self.memory.remove(trace.memory_id) {
+                let map_id = map.insert(&tensor);
+                report.migrated.push((trace.memory_id, map_id));
+            }
+        }
+
+        if !report.migrated.is_empty() {
+            println!("Consolidated {} stable memories into the cognitive map", report.migrated.len());
+        }
+        report
+    }
+}
+
+/// Alternate fresh and replayed experiences, appending whichever runs longer
+fn interleave(fresh: &[Experience], replayed: &[Experience]) -> Vec<Experience> {
+    let mut batch = Vec::with_capacity(fresh.len() + replayed.len());
+    let mut fresh_iter = fresh.iter();
+    let mut replay_iter = replayed.iter();
+    loop {
+        match (fresh_iter.next(), replay_iter.next()) {
+            (None, None) => break,
+            (f, r) => batch.extend(f.into_iter().chain(r).cloned()),
+        }
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_replay_priorities_and_migration() {
+        let mut consolidator = MemoryConsolidator::new(QuantumMemoryManager::new(16), ReplayPriority::Reward);
+        consolidator.replay_batch = 2;
+        consolidator.stability_threshold = 2;
+
+        let a = consolidator.record(&MorphicTensor::from_data(array![0.0, 0.0]), 0, 0.1);
+        let b = consolidator.record(&MorphicTensor::from_data(array![1.0, 0.0]), 1, -2.0);
+        let c = consolidator.record(&MorphicTensor::from_data(array![0.0, 3.0]), 2, 0.5);
+        assert_eq!(consolidator.replay_order(), vec![b, c, a]);
+
+        consolidator.priority = ReplayPriority::Novelty;
+        assert_eq!(consolidator.replay_order()[0], c);
+
+        let mut agent = QRLAgent::new(2, 3, 0.01, 0.9);
+        let mut map = CognitiveMap::new();
+        consolidator.priority = ReplayPriority::Reward;
+        let first = consolidator.consolidate(&mut agent, &mut map, &[]);
+        assert_eq!(first.replayed, vec![b, c]);
+        assert!(first.migrated.is_empty());
+
+        let second = consolidator.consolidate(&mut agent, &mut map, &[]);
+        assert_eq!(second.migrated.len(), 2);
+        assert_eq!(map.size(), 2);
+        assert_eq!(consolidator.traces().len(), 1);
+        assert!(consolidator.memory.peek(b).is_none());
+    }
+
+    #[test]
+    fn test_interleave_alternates() {
+        let e = |reward| Experience { state: array![0.0], action: 0, reward };
+        let batch = interleave(&[e(1.0), e(2.0), e(3.0)], &[e(-1.0)]);
+        let rewards: Vec<f64> = batch.iter().map(|x| x.reward).collect();
+        assert_eq!(rewards, vec![1.0, -1.0, 2.0, 3.0]);
+    }
+}
@@ -0,0 +1,414 @@
+// Place-cell and grid-cell population codes for spatial coordinates
+#![allow(dead_code)]
+
+use ndarray::Array1;
+use std::f64::consts::{PI, TAU};
+use crate::core::tensor::MorphicTensor;
+
+/// Encodes positions as neural population activity and decodes them back
+pub trait SpatialEncoder {
+    /// Number of cells in the population
+    fn dimension(&self) -> usize;
+
+    fn encode(&self, position: [f64; 2]) -> Array1<f64>;
+
+    /// Position best explained by a population activity vector
+    fn decode(&self, activity: &Array1<f64>) -> Option<[f64; 2]>;
+
+    fn encode_tensor(&self, tensor: &MorphicTensor) -> Array1<f64> {
+        self.encode(tensor.position())
+    }
+}
+
+/// Population of place cells with Gaussian tuning curves
+pub struct PlaceCellEncoder {
+    pub centers: Vec<[f64; 2]>,
+    /// Tuning width σ shared by every cell
+    pub width: f64,
+}
+
+impl PlaceCellEncoder {
+    pub fn new(centers: Vec<[f64; 2]>, width: f64) -> Self {
+        PlaceCellEncoder { centers, width }
+    }
+
+    /// Place cells tiling the box `[min, max]` with `per_axis` cells along each side
+    pub fn tiled(min: [f64; 2], max: [f64; 2], per_axis: usize, width: f64) -> Self {
+        let step = |lo: f64, hi: f64, i: usize| {
+            if per_axis > 1 { lo + (hi - lo) * i as f64 / (per_axis - 1) as f64 } else { (lo + hi) / 2.0 }
+        };
+        let centers = (0..per_axis)
+            .flat_map(|j| (0..per_axis).map(move |i| [step(min[0], max[0], i), step(min[1], max[1], j)]))
+            .collect();
+        PlaceCellEncoder::new(centers, width)
+    }
+
+    fn rate(&self, center: [f64; 2], position: [f64; 2]) -> f64 {
+        let dx = position[0] - center[0];
+        let dy = position[1] - center[1];
+        (-(dx * dx + dy * dy) / (2.0 * self.width * self.width)).exp()
+    }
+
+    /// Activity-weighted mean of the cell centres
+    fn population_vector(&self, activity: &Array1<f64>) -> Option<[f64; 2]> {
+        let total: f64 = activity.iter().map(|a| a.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut position = [0.0, 0.0];
+        for (center, a) in self.centers.iter().zip(activity.iter()) {
+            position[0] += center[0] * a.max(0.0) / total;
+            position[1] += center[1] * a.max(0.0) / total;
+        }
+        Some(position)
+    }
+}
+
+impl SpatialEncoder for PlaceCellEncoder {
+    fn dimension(&self) -> usize {
+        self.centers.len()
+    }
+
+    fn encode(&self, position: [f64; 2]) -> Array1<f64> {
+        self.centers.iter().map(|&c| self.rate(c, position)).collect()
+    }
+
+    /// Log-linear least-squares fit of the Gaussian tuning curves
+    ///
+    /// For Gaussian tuning, ln aᵢ − ln a₀ is linear in the position, so active cells
+    /// relative to the most active one give an overdetermined linear system. Falls back
+    /// to the population vector when fewer than three non-collinear cells respond.
+    fn decode(&self, activity: &Array1<f64>) -> Option<[f64; 2]> {
+        if activity.len() != self.centers.len() || self.width <= 0.0 {
+            return None;
+        }
+        let (peak, &peak_rate) = activity.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))?;
+        if !peak_rate.is_finite() || peak_rate <= 0.0 {
+            return None;
+        }
+
+        let c0 = self.centers[peak];
+        let two_var = 2.0 * self.width * self.width;
+        // Normal equations of rows [2(cᵢ − c₀)] · x = 2σ²(ln aᵢ − ln a₀) + |cᵢ|² − |c₀|²
+        let (mut ata, mut atb) = ([[0.0; 2]; 2], [0.0; 2]);
+        for (i, &a) in activity.iter().enumerate() {
+            if i == peak || a < peak_rate * 1e-6 {
+                continue;
+            }
+            let ci = self.centers[i];
+            let row = [2.0 * (ci[0] - c0[0]), 2.0 * (ci[1] - c0[1])];
+            let rhs = two_var * (a / peak_rate).ln()
+                + (ci[0] * ci[0] + ci[1] * ci[1]) - (c0[0] * c0[0] + c0[1] * c0[1]);
+            for r in 0..2 {
+                atb[r] += row[r] * rhs;
+                for c in 0..2 {
+                    ata[r][c] += row[r] * row[c];
+                }
+            }
+        }
+
+        let det = ata[0][0] * ata[1][1] - ata[0][1] * ata[1][0];
+        let scale = ata[0][0] + ata[1][1];
+        if det.abs() <= 1e-12 * scale * scale || scale == 0.0 {
+            return self.population_vector(activity);
+        }
+        Some([
+            (ata[1][1] * atb[0] - ata[0][1] * atb[1]) / det,
+            (ata[0][0] * atb[1] - ata[1][0] * atb[0]) / det,
+        ])
+    }
+}
+
+/// One grid-cell module: a hexagonal lattice of firing fields at a single scale
+#[derive(Debug, Clone, Copy)]
+pub struct GridModule {
+    /// Distance between neighbouring firing fields
+    pub spacing: f64,
+    /// Rotation of the lattice in radians
+    pub orientation: f64,
+}
+
+impl GridModule {
+    pub fn new(spacing: f64, orientation: f64) -> Self {
+        GridModule { spacing, orientation }
+    }
+
+    /// Lattice basis vectors, 60° apart
+    pub fn basis(&self) -> [[f64; 2]; 2] {
+        let a = self.orientation;
+        let b = a + PI / 3.0;
+        [
+            [self.spacing * a.cos(), self.spacing * a.sin()],
+            [self.spacing * b.cos(), self.spacing * b.sin()],
+        ]
+    }
+
+    /// Reciprocal wave vectors k₁, k₂ with kᵢ·aⱼ = 2πδᵢⱼ, plus k₃ = −(k₁ + k₂)
+    pub fn wave_vectors(&self) -> [[f64; 2]; 3] {
+        let [a1, a2] = self.basis();
+        let det = a1[0] * a2[1] - a1[1] * a2[0];
+        let k1 = [TAU * a2[1] / det, -TAU * a2[0] / det];
+        let k2 = [-TAU * a1[1] / det, TAU * a1[0] / det];
+        [k1, k2, [-(k1[0] + k2[0]), -(k1[1] + k2[1])]]
+    }
+
+    /// Firing rate in [0, 1] of the cell whose lattice is shifted by `offset`
+    pub fn rate(&self, position: [f64; 2], offset: [f64; 2]) -> f64 {
+        let x = [position[0] - offset[0], position[1] - offset[1]];
+        let g: f64 = self.wave_vectors().iter().map(|k| (k[0] * x[0] + k[1] * x[1]).cos()).sum::<f64>() / 3.0;
+        (g + 0.5) / 1.5
+    }
+
+    /// Lattice phase (k₁·x, k₂·x) of a position, wrapped into [0, 2π)
+    pub fn phase_of(&self, position: [f64; 2]) -> [f64; 2] {
+        let [k1, k2, _] = self.wave_vectors();
+        [
+            (k1[0] * position[0] + k1[1] * position[1]).rem_euclid(TAU),
+            (k2[0] * position[0] + k2[1] * position[1]).rem_euclid(TAU),
+        ]
+    }
+
+    /// Position with lattice phase `phase` closest to `near`, solving
+    /// kⱼ·x = θⱼ + 2πnⱼ with nⱼ rounded from `near`
+    pub fn position_near(&self, phase: [f64; 2], near: [f64; 2]) -> [f64; 2] {
+        let [k1, k2, _] = self.wave_vectors();
+        let unwrap = |k: [f64; 2], theta: f64| {
+            let kx = k[0] * near[0] + k[1] * near[1];
+            theta + TAU * ((kx - theta) / TAU).round()
+        };
+        let (b1, b2) = (unwrap(k1, phase[0]), unwrap(k2, phase[1]));
+        let det = k1[0] * k2[1] - k1[1] * k2[0];
+        [(b1 * k2[1] - k1[1] * b2) / det, (k1[0] * b2 - b1 * k2[0]) / det]
+    }
+}
+
+/// Most coarse-module lattice points `GridCellEncoder::decode` will consider
+pub const MAX_DECODE_CANDIDATES: usize = 4096;
+
+/// Candidates carried from one module to the next while decoding
+const DECODE_BEAM_WIDTH: usize = 64;
+
+/// Multi-scale grid-cell population
+///
+/// Each module holds `cells_per_axis²` cells whose lattices are offset evenly across
+/// the unit rhombus, so each module's activity pins the position down modulo its
+/// lattice. Combining modules of different spacing disambiguates the position inside
+/// `bounds`.
+pub struct GridCellEncoder {
+    pub modules: Vec<GridModule>,
+    pub cells_per_axis: usize,
+    /// Box `(min, max)` searched when decoding
+    pub bounds: ([f64; 2], [f64; 2]),
+}
+
+impl GridCellEncoder {
+    pub fn new(modules: Vec<GridModule>, cells_per_axis: usize, min: [f64; 2], max: [f64; 2]) -> Self {
+        GridCellEncoder { modules, cells_per_axis, bounds: (min, max) }
+    }
+
+    /// Modules whose spacing grows geometrically by `ratio`, each rotated by `rotation`
+    /// relative to the previous one
+    pub fn multi_scale(base_spacing: f64, ratio: f64, count: usize, rotation: f64, min: [f64; 2], max: [f64; 2]) -> Self {
+        let modules = (0..count)
+            .map(|m| GridModule::new(base_spacing * ratio.powi(m as i32), rotation * m as f64))
+            .collect();
+        GridCellEncoder::new(modules, 4, min, max)
+    }
+
+    fn cells_per_module(&self) -> usize {
+        self.cells_per_axis * self.cells_per_axis
+    }
+
+    fn offsets(&self, module: &GridModule) -> Vec<[f64; 2]> {
+        let [a1, a2] = module.basis();
+        let n = self.cells_per_axis;
+        (0..n)
+            .flat_map(|j| (0..n).map(move |i| {
+                let (u, v) = (i as f64 / n as f64, j as f64 / n as f64);
+                [u * a1[0] + v * a2[0], u * a1[1] + v * a2[1]]
+            }))
+            .collect()
+    }
+
+    /// Recover each module's lattice phase from its block of activity
+    ///
+    /// With at least three offsets per axis the Fourier sum over offsets isolates the
+    /// single wave-vector component, giving kⱼ·x exactly for noiseless activity.
+    pub fn module_phases(&self, activity: &Array1<f64>) -> Option<Vec<[f64; 2]>> {
+        if activity.len() != self.dimension() || self.cells_per_axis < 3 {
+            return None;
+        }
+        let per_module = self.cells_per_module();
+        self.modules.iter().enumerate()
+            .map(|(m, module)| {
+                let block = activity.slice(ndarray::s![m * per_module..(m + 1) * per_module]);
+                let [k1, k2, _] = module.wave_vectors();
+                let mut phase = [0.0; 2];
+                for (j, k) in [k1, k2].iter().enumerate() {
+                    let (mut re, mut im) = (0.0, 0.0);
+                    for (offset, &rate) in self.offsets(module).iter().zip(block.iter()) {
+                        let angle = k[0] * offset[0] + k[1] * offset[1];
+                        re += rate * angle.cos();
+                        im += rate * angle.sin();
+                    }
+                    if re.hypot(im) <= f64::EPSILON {
+                        return None;
+                    }
+                    phase[j] = im.atan2(re).rem_euclid(TAU);
+                }
+                Some(phase)
+            })
+            .collect()
+    }
+
+    /// Phase mismatch of `position` summed over the modules listed in `modules`
+    fn phase_error_of(&self, phases: &[[f64; 2]], modules: &[usize], position: [f64; 2]) -> f64 {
+        modules.iter()
+            .map(|&m| {
+                let actual = self.modules[m].phase_of(position);
+                (0..2).map(|j| 1.0 - (actual[j] - phases[m][j]).cos()).sum::<f64>()
+            })
+            .sum()
+    }
+}
+
+impl SpatialEncoder for GridCellEncoder {
+    fn dimension(&self) -> usize {
+        self.modules.len() * self.cells_per_module()
+    }
+
+    fn encode(&self, position: [f64; 2]) -> Array1<f64> {
+        self.modules.iter()
+            .flat_map(|module| {
+                self.offsets(module).into_iter().map(move |offset| module.rate(position, offset))
+            })
+            .collect()
+    }
+
+    /// Decode coarse-to-fine: every lattice point of the coarsest module inside
+    /// `bounds` is a candidate, and each finer module in turn snaps the best
+    /// candidates onto its own lattice, ending on the finest module's exact solution
+    ///
+    /// Returns `None` when the coarsest module alone would leave more than
+    /// `MAX_DECODE_CANDIDATES` positions in `bounds`.
+    fn decode(&self, activity: &Array1<f64>) -> Option<[f64; 2]> {
+        let phases = self.module_phases(activity)?;
+        let mut order: Vec<usize> = (0..self.modules.len()).collect();
+        order.sort_by(|&a, &b| self.modules[b].spacing.total_cmp(&self.modules[a].spacing));
+        let (&coarsest, finer) = order.split_first()?;
+
+        let (min, max) = self.bounds;
+        let coarse = self.modules[coarsest];
+        if !coarse.spacing.is_finite() || coarse.spacing <= 0.0 || min[0] > max[0] || min[1] > max[1] {
+            return None;
+        }
+
+        // Lattice coordinates of the bounds' corners relative to one phase solution
+        let origin = coarse.position_near(phases[coarsest], min);
+        let [a1, a2] = coarse.basis();
+        let [k1, k2, _] = coarse.wave_vectors();
+        let (mut lo, mut hi) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+        for corner in [min, [max[0], min[1]], [min[0], max[1]], max] {
+            let d = [corner[0] - origin[0], corner[1] - origin[1]];
+            for (j, k) in [k1, k2].iter().enumerate() {
+                let n = (k[0] * d[0] + k[1] * d[1]) / TAU;
+                lo[j] = lo[j].min(n.floor());
+                hi[j] = hi[j].max(n.ceil());
+            }
+        }
+        if (hi[0] - lo[0] + 1.0) * (hi[1] - lo[1] + 1.0) > MAX_DECODE_CANDIDATES as f64 {
+            return None;
+        }
+        let margin = coarse.spacing / 2.0;
+        let inside = |p: [f64; 2]| (0..2).all(|j| p[j] >= min[j] - margin && p[j] <= max[j] + margin);
+        let mut candidates: Vec<[f64; 2]> = (lo[0] as i64..=hi[0] as i64)
+            .flat_map(|n1| (lo[1] as i64..=hi[1] as i64).map(move |n2| (n1 as f64, n2 as f64)))
+            .map(|(n1, n2)| [origin[0] + n1 * a1[0] + n2 * a2[0], origin[1] + n1 * a1[1] + n2 * a2[1]])
+            .filter(|&p| inside(p))
+            .collect();
+
+        for (stage, &m) in finer.iter().enumerate() {
+            let module = self.modules[m];
+            let [b1, b2] = module.basis();
+            let used = &order[..stage + 2];
+            let mut refined: Vec<(f64, [f64; 2])> = candidates.iter()
+                .flat_map(|&estimate| {
+                    let snapped = module.position_near(phases[m], estimate);
+                    // The estimate may sit up to a cell away from the right lattice point
+                    (-1..=1).flat_map(move |i| (-1..=1).map(move |j| {
+                        let (i, j) = (i as f64, j as f64);
+                        [snapped[0] + i * b1[0] + j * b2[0], snapped[1] + i * b1[1] + j * b2[1]]
+                    }))
+                })
+                .filter(|&p| inside(p))
+                .map(|p| (self.phase_error_of(&phases, used, p), p))
+                .collect();
+            refined.sort_by(|a, b| a.0.total_cmp(&b.0));
+            refined.truncate(DECODE_BEAM_WIDTH);
+            candidates = refined.into_iter().map(|(_, p)| p).collect();
+        }
+
+        candidates.into_iter()
+            .map(|p| (self.phase_error_of(&phases, &order, p), p))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, p)| p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_place_cells_round_trip() {
+        let encoder = PlaceCellEncoder::tiled([0.0, 0.0], [10.0, 10.0], 11, 1.5);
+        assert_eq!(encoder.dimension(), 121);
+        for position in [[3.3, 7.1], [0.2, 9.8], [5.0, 5.0]] {
+            let decoded = encoder.decode(&encoder.encode(position)).unwrap();
+            assert_relative_eq!(decoded[0], position[0], epsilon = 1e-6);
+            assert_relative_eq!(decoded[1], position[1], epsilon = 1e-6);
+        }
+        assert!(encoder.decode(&Array1::zeros(121)).is_none());
+    }
+
+    #[test]
+    fn test_grid_modules_are_hexagonal() {
+        let module = GridModule::new(2.0, 0.3);
+        let [a1, a2] = module.basis();
+        let origin = module.rate([0.0, 0.0], [0.0, 0.0]);
+        assert_relative_eq!(origin, 1.0, epsilon = 1e-12);
+        // Firing fields repeat along both lattice vectors and their difference
+        for shift in [a1, a2, [a2[0] - a1[0], a2[1] - a1[1]]] {
+            assert_relative_eq!(module.rate(shift, [0.0, 0.0]), 1.0, epsilon = 1e-12);
+        }
+        assert!(module.rate([a1[0] / 2.0, a1[1] / 2.0], [0.0, 0.0]) < 0.5);
+    }
+
+    #[test]
+    fn test_multi_scale_grid_code_round_trip() {
+        let encoder = GridCellEncoder::multi_scale(0.7, 1.42, 4, 0.2, [0.0, 0.0], [8.0, 8.0]);
+        assert_eq!(encoder.dimension(), 4 * 16);
+        for position in [[1.234, 6.543], [7.9, 0.1], [4.0, 4.0]] {
+            let decoded = encoder.decode(&encoder.encode(position)).unwrap();
+            assert_relative_eq!(decoded[0], position[0], epsilon = 1e-6);
+            assert_relative_eq!(decoded[1], position[1], epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_grid_decode_scales_to_wide_bounds() {
+        let encoder = GridCellEncoder::multi_scale(0.5, 1.5, 6, 0.2, [-50.0, -50.0], [50.0, 50.0]);
+        for position in [[-43.21, 17.5], [0.0, 0.0], [49.9, -49.9]] {
+            let decoded = encoder.decode(&encoder.encode(position)).unwrap();
+            assert_relative_eq!(decoded[0], position[0], epsilon = 1e-6);
+            assert_relative_eq!(decoded[1], position[1], epsilon = 1e-6);
+        }
+
+        // Bounds far beyond what the coarsest module can cover are refused, not searched
+        let huge = GridCellEncoder::multi_scale(0.5, 1.5, 6, 0.2, [-1e6, -1e6], [1e6, 1e6]);
+        assert!(huge.decode(&huge.encode([1.0, 2.0])).is_none());
+    }
+}
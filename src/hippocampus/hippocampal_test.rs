@@ -1,9 +1,14 @@
 // Hippocampal Architecture Test
 use morph::hippocampus::index::CognitiveMap;
 use morph::hippocampus::pattern_completion::PatternCompleter;
-use morph::hippocampus::oscillation::Oscillator;
+use morph::hippocampus::encoding::{GridCellEncoder, PlaceCellEncoder, SpatialEncoder};
+use morph::hippocampus::oscillation::{Oscillator, PlaceField};
 use morph::hippocampus::memory::QuantumMemoryManager;
+use morph::hippocampus::consolidation::{Experience, MemoryConsolidator, ReplayPriority};
+use morph::learning::QRLAgent;
 use morph::core::tensor::MorphicTensor;
+use morph::quantum::state::QuantumState;
+use ndarray::array;
 
 fn main() {
     println!("Testing Hippocampal Architecture...");
@@ -22,16 +27,87 @@ fn main() {
 
     println!("Cognitive map contains {} tensors", cognitive_map.size());
 
+    // Test place-field topology over a small arena
+    let mut arena = CognitiveMap::new();
+    for k in 0..16 {
+        let (x, y) = ((k % 4) as f64, (k / 4) as f64);
+        let mut tensor = MorphicTensor::from_data(array![x, y, x * y]);
+        tensor.spatial.coordinates = [x, y];
+        arena.insert(&tensor);
+    }
+    if let Some(field) = arena.place_field([1.2, 1.9]) {
+        println!("Place field for [1.2, 1.9]: ids {:?}, area {:?}", field.ids, field.area());
+    }
+    println!("Memories adjacent to 5: {:?}", arena.adjacent_memories(5));
+    println!("Interpolated memory at [1.5, 2.5]: {:?}", arena.interpolate([1.5, 2.5]));
+
+    // Test spatial memory queries
+    println!("3 nearest to [2.2, 2.2]: {:?}", arena.k_nearest([2.2, 2.2], 3));
+    println!("Within 1.0 of [0.0, 0.0]: {:?}", arena.within_radius([0.0, 0.0], 1.0));
+    println!("Inside [1,1]-[2,2]: {:?}", arena.within_bounds([1.0, 1.0], [2.0, 2.0]));
+    if let Some(removed) = arena.remove(5) {
+        println!("Removed memory 5 at {:?}; nearest to it is now {:?}",
+                 removed.position(), arena.nearest_neighbor(removed.position()));
+    }
+    arena.move_to(0, [10.0, 10.0]);
+    println!("Memory 0 moved to {:?}", arena.get(0).map(|t| t.position()));
+
     // Test pattern completion
-    let pattern_completer = PatternCompleter::new(cognitive_map, 0.5);
+    let mut pattern_completer = PatternCompleter::new(cognitive_map, 0.5);
     let partial_point = [1.1, 2.1];
     if let Some(id) = pattern_completer.complete_pattern(partial_point) {
         println!("Pattern completed for point {:?} with ID: {}", partial_point, id);
     }
 
+    // Test content-addressable recall
+    let engrams = [
+        array![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0],
+        array![1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0],
+        array![-1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0, 1.0],
+    ];
+    for (k, engram) in engrams.iter().enumerate() {
+        let mut memory = MorphicTensor::from_data(engram.clone());
+        memory.spatial.coordinates = [k as f64, 5.0];
+        pattern_completer.remember(&memory);
+    }
+    let cue = array![1.0, -1.0, f64::NAN, f64::NAN, 1.0, 1.0, f64::NAN, -1.0];
+    match pattern_completer.complete(&cue) {
+        Some((id, recall)) => println!(
+            "Recalled memory {} in {} iterations (confidence {:.3}, energy {:.4}): {:.2}",
+            id, recall.iterations, recall.confidence, recall.energy, recall.pattern),
+        None => println!("Cue rejected below completion threshold"),
+    }
+    let ambiguous = (&engrams[0] + &engrams[1]) / 2.0;
+    pattern_completer.completion_threshold = 0.9;
+    println!("Ambiguous cue accepted: {}", pattern_completer.complete(&ambiguous).is_some());
+
     // Test theta-gamma oscillation
     let oscillator = Oscillator::new(4.0, 40.0, 1.0);
-    oscillator.simulate_cycle();
+    let cycle = oscillator.simulate_cycle();
+    let peak = cycle.signal().iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    println!("Sampled {} points at {} Hz, peak LFP {:.3}, modulation index {:.4}",
+             cycle.len(), cycle.sample_rate, peak, oscillator.simulate(2.0).modulation_index(18));
+
+    // Test phase precession through a place field
+    let field = PlaceField::new([1.0, 2.0], 1.0);
+    for x in [0.25, 0.75, 1.25, 1.75] {
+        oscillator.entangle_with_position([x, 2.0], &field, [1.0, 0.0]);
+    }
+    for slot in pattern_completer.theta_gamma_coupling(&oscillator, [1.0, 2.0], [1.0, 0.0], 3.0) {
+        println!("  memory {} fires at phase {:.3} (γ cycle {})", slot.id, slot.phase, slot.gamma_cycle);
+    }
+
+    // Test place-cell and grid-cell population codes
+    let place_cells = PlaceCellEncoder::tiled([0.0, 0.0], [5.0, 5.0], 6, 1.0);
+    let grid_cells = GridCellEncoder::multi_scale(0.5, 1.42, 3, 0.15, [0.0, 0.0], [5.0, 5.0]);
+    for encoder in [&place_cells as &dyn SpatialEncoder, &grid_cells] {
+        let code = encoder.encode([1.7, 3.2]);
+        println!("{}-cell code decodes to {:?}", code.len(), encoder.decode(&code));
+    }
+    let (code_ids, codes) = pattern_completer.cognitive_map.encode_memories(&grid_cells);
+    println!("Grid codes for memories {:?}: {:?}", code_ids, codes.dim());
+    println!("Nearest memory to grid code: {:?}",
+             pattern_completer.cognitive_map.nearest_to_code(&grid_cells, &codes.row(0).to_owned()));
 
     // Test spatial entanglement
     let entanglement_strength = pattern_completer.cognitive_map.spatial_entanglement(
@@ -40,9 +116,42 @@ fn main() {
     println!("Spatial entanglement strength: {}", entanglement_strength);
 
     // Test quantum memory management
-    let memory_manager = QuantumMemoryManager::new(100);
-    memory_manager.store(&tensor1);
-    memory_manager.retrieve(0);
+    let mut memory_manager = QuantumMemoryManager::new(2);
+    let first = memory_manager.store(&tensor1);
+    println!("Retrieved memory {}: {}", first, memory_manager.retrieve(first).is_some());
+    for k in 0..4 {
+        let mut superposed = MorphicTensor::from_data(array![k as f64 + 0.3]);
+        superposed.quantum_state = QuantumState::Superposition;
+        memory_manager.store(&superposed);
+    }
+    println!("Memory tiers: void {:?}, superposition {:?}, classical {:?}",
+             memory_manager.ids_in(QuantumState::Void),
+             memory_manager.ids_in(QuantumState::Superposition),
+             memory_manager.ids_in(QuantumState::Collapsed));
+
+
+    // Test consolidation: replay short-term memories into an agent and the cognitive map
+    let mut consolidator = MemoryConsolidator::new(QuantumMemoryManager::new(32), ReplayPriority::Novelty);
+    consolidator.consolidation_interval = 5;
+    consolidator.stability_threshold = 2;
+    let mut agent = QRLAgent::new(3, 2, 0.01, 0.95);
+    let mut long_term = CognitiveMap::new();
+    for step in 0..20 {
+        let x = step as f64 * 0.1;
+        let mut episode = MorphicTensor::from_data(array![x.sin(), x.cos(), x]);
+        episode.spatial.coordinates = [x, x * 0.5];
+        let action = step % 2;
+        let reward = if action == 0 { x } else { -x };
+        consolidator.record(&episode, action, reward);
+
+        let fresh = [Experience { state: episode.to_array(), action, reward }];
+        if let Some(report) = consolidator.tick(&mut agent, &mut long_term, &fresh) {
+            println!("Step {}: replayed {:?}, migrated {} memories",
+                     step, report.replayed, report.migrated.len());
+        }
+    }
+    println!("Long-term memories: {}, awaiting consolidation: {}",
+             long_term.size(), consolidator.traces().len());
 
     println!("✅ Hippocampal tests completed!");
 }
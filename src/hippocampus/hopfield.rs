@@ -0,0 +1,183 @@
+// Continuous Hopfield attractor network for content-addressable recall
+#![allow(dead_code)]
+
+use ndarray::Array1;
+
+/// Result of settling a cue into the attractor landscape
+#[derive(Debug, Clone)]
+pub struct HopfieldRecall {
+    pub pattern: Array1<f64>,
+    /// Index of the attractor carrying the largest attention weight
+    pub attractor: usize,
+    /// Attention weight of that attractor in the final state (0..=1)
+    pub confidence: f64,
+    pub energy: f64,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+/// Modern (dense associative memory) Hopfield network
+///
+/// Stored patterns X are fixed points of ξ ← X·softmax(β·Xᵀξ), which descends the energy
+/// E(ξ) = −β⁻¹·log Σᵢ exp(β·xᵢᵀξ) + ½·ξᵀξ + β⁻¹·log N + ½·M², with M the largest
+/// pattern norm. β sets how sharply the landscape separates attractors relative to the
+/// scale of the stored dot products.
+pub struct HopfieldNetwork {
+    pub patterns: Vec<Array1<f64>>,
+    pub beta: f64,
+    pub max_iterations: usize,
+    pub tolerance: f64,
+}
+
+impl HopfieldNetwork {
+    pub fn new(beta: f64) -> Self {
+        HopfieldNetwork {
+            patterns: Vec::new(),
+            beta,
+            max_iterations: 100,
+            tolerance: 1e-8,
+        }
+    }
+
+    /// Dimension of the stored patterns (0 while empty)
+    pub fn dimension(&self) -> usize {
+        self.patterns.first().map(|p| p.len()).unwrap_or(0)
+    }
+
+    /// Store a pattern as an attractor and return its index
+    pub fn store(&mut self, pattern: Array1<f64>) -> Result<usize, String> {
+        if !self.patterns.is_empty() && pattern.len() != self.dimension() {
+            return Err(format!("Pattern has dimension {} but the network stores dimension {}",
+                               pattern.len(), self.dimension()));
+        }
+        if pattern.iter().any(|x| !x.is_finite()) {
+            return Err("Attractor patterns must be finite".to_string());
+        }
+        self.patterns.push(pattern);
+        Ok(self.patterns.len() - 1)
+    }
+
+    /// Attention over stored patterns: softmax(β·Xᵀξ)
+    pub fn attention(&self, state: &Array1<f64>) -> Vec<f64> {
+        let logits: Vec<f64> = self.patterns.iter().map(|p| self.beta * p.dot(state)).collect();
+        let max = logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let exps: Vec<f64> = logits.iter().map(|l| (l - max).exp()).collect();
+        let total: f64 = exps.iter().sum();
+        exps.into_iter().map(|e| e / total).collect()
+    }
+
+    /// Hopfield energy of a state
+    pub fn energy(&self, state: &Array1<f64>) -> f64 {
+        if self.patterns.is_empty() {
+            return 0.5 * state.dot(state);
+        }
+        let logits: Vec<f64> = self.patterns.iter().map(|p| self.beta * p.dot(state)).collect();
+        let max = logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let log_sum_exp = max + logits.iter().map(|l| (l - max).exp()).sum::<f64>().ln();
+        let max_norm_sq = self.patterns.iter().map(|p| p.dot(p)).fold(0.0, f64::max);
+
+        -log_sum_exp / self.beta
+            + 0.5 * state.dot(state)
+            + (self.patterns.len() as f64).ln() / self.beta
+            + 0.5 * max_norm_sq
+    }
+
+    /// One synchronous update ξ ← X·softmax(β·Xᵀξ)
+    pub fn update(&self, state: &Array1<f64>) -> Array1<f64> {
+        let mut next = Array1::zeros(self.dimension());
+        for (pattern, weight) in self.patterns.iter().zip(self.attention(state)) {
+            next.scaled_add(weight, pattern);
+        }
+        next
+    }
+
+    /// Settle a cue into its attractor
+    ///
+    /// Non-finite cue entries (e.g. NaN) mark unknown components and start at zero, so
+    /// the first update matches only on the known part of the pattern.
+    pub fn recall(&self, cue: &Array1<f64>) -> Option<HopfieldRecall> {
+        if self.patterns.is_empty() || cue.len() != self.dimension() {
+            return None;
+        }
+
+        let mut state = cue.mapv(|x| if x.is_finite() { x } else { 0.0 });
+        let mut iterations = 0;
+        let mut converged = false;
+
+        while iterations < self.max_iterations {
+            let next = self.update(&state);
+            let change = (&next - &state).mapv(|d| d * d).sum().sqrt();
+            state = next;
+            iterations += 1;
+            if change < self.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        let attention = self.attention(&state);
+        let (attractor, confidence) = attention.iter()
+            .cloned()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |best, (i, w)| if w > best.1 { (i, w) } else { best });
+
+        Some(HopfieldRecall {
+            energy: self.energy(&state),
+            pattern: state,
+            attractor,
+            confidence,
+            iterations,
+            converged,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn bipolar(bits: &[u8]) -> Array1<f64> {
+        bits.iter().map(|&b| if b == 1 { 1.0 } else { -1.0 }).collect()
+    }
+
+    fn network() -> HopfieldNetwork {
+        let mut network = HopfieldNetwork::new(1.0);
+        network.store(bipolar(&[1, 1, 1, 1, 0, 0, 0, 0, 1, 0, 1, 0, 1, 0, 1, 0])).unwrap();
+        network.store(bipolar(&[0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1])).unwrap();
+        network.store(bipolar(&[1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0, 0, 1, 1, 0])).unwrap();
+        network
+    }
+
+    #[test]
+    fn test_recall_from_noisy_and_partial_cues() {
+        let network = network();
+        let target = network.patterns[1].clone();
+
+        let mut noisy = target.clone();
+        for i in [0, 5, 9] {
+            noisy[i] = -noisy[i];
+        }
+        let recall = network.recall(&noisy).unwrap();
+        assert_eq!(recall.attractor, 1);
+        assert!(recall.converged);
+        for (a, b) in recall.pattern.iter().zip(target.iter()) {
+            assert_relative_eq!(*a, *b, epsilon = 1e-3);
+        }
+
+        let mut partial = target.clone();
+        for i in 8..16 {
+            partial[i] = f64::NAN;
+        }
+        assert_eq!(network.recall(&partial).unwrap().attractor, 1);
+    }
+
+    #[test]
+    fn test_energy_decreases_during_recall() {
+        let mut network = network();
+        let cue = Array1::from_vec(vec![0.3; 16]);
+        let next = network.update(&cue);
+        assert!(network.energy(&next) <= network.energy(&cue));
+        assert!(network.store(Array1::zeros(3)).is_err());
+    }
+}
@@ -2,35 +2,154 @@
 #![allow(dead_code)]
 
 use kiddo::{KdTree, SquaredEuclidean};
+use ndarray::{Array1, Array2};
+use std::collections::HashMap;
 use crate::core::tensor::MorphicTensor;
+use crate::hippocampus::encoding::SpatialEncoder;
+use crate::hippocampus::topology::{PlaceCellTopology, PlaceRegion};
 
+/// Spatial memory index over stored tensors
+///
+/// Tensors are owned by the map under stable IDs that are never reused. The KD-tree
+/// indexes distinct positions ("sites"); tensors sharing a position share a site, which
+/// keeps the tree valid when many memories are stored at the same point.
 pub struct CognitiveMap {
-    pub tree: KdTree<f64, 2>,  // 2-dimensional tree
+    pub tree: KdTree<f64, 2>,  // 2-dimensional tree over site IDs
+    pub topology: PlaceCellTopology,  // Delaunay/Voronoi layer over memory IDs
+    memories: HashMap<u64, MorphicTensor>,
+    site_members: HashMap<u64, Vec<u64>>,
+    site_at: HashMap<(u64, u64), u64>,
     counter: u64,  // Use u64 to match KdTree's item type
+    site_counter: u64,
+}
+
+impl Default for CognitiveMap {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CognitiveMap {
     pub fn new() -> Self {
         CognitiveMap {
             tree: KdTree::new(),
+            topology: PlaceCellTopology::new(),
+            memories: HashMap::new(),
+            site_members: HashMap::new(),
+            site_at: HashMap::new(),
             counter: 0,
+            site_counter: 0,
         }
     }
 
-    pub fn insert(&mut self, tensor: &MorphicTensor) {
-        let point = tensor.position();
-        self.tree.add(&point, self.counter);
+    /// Store a copy of the tensor and return its ID
+    pub fn insert(&mut self, tensor: &MorphicTensor) -> usize {
+        let id = self.counter;
         self.counter += 1;
+        self.index(id, tensor);
+        self.memories.insert(id, tensor.clone());
+        id as usize
+    }
+
+    /// Replace a stored tensor, re-indexing it if its position changed
+    pub fn update(&mut self, id: usize, tensor: &MorphicTensor) -> bool {
+        let id = id as u64;
+        let Some(previous) = self.memories.get(&id) else {
+            return false;
+        };
+        let previous_position = previous.position();
+        self.unindex(id, previous_position);
+        self.index(id, tensor);
+        self.memories.insert(id, tensor.clone());
+        true
+    }
+
+    /// Move a stored tensor to a new position
+    pub fn move_to(&mut self, id: usize, position: [f64; 2]) -> bool {
+        let Some(mut tensor) = self.get(id).cloned() else {
+            return false;
+        };
+        tensor.spatial.coordinates = position;
+        self.update(id, &tensor)
+    }
+
+    /// Remove a tensor from the map
+    pub fn remove(&mut self, id: usize) -> Option<MorphicTensor> {
+        let tensor = self.memories.remove(&(id as u64))?;
+        self.unindex(id as u64, tensor.position());
+        Some(tensor)
+    }
+
+    pub fn get(&self, id: usize) -> Option<&MorphicTensor> {
+        self.memories.get(&(id as u64))
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.memories.contains_key(&(id as u64))
+    }
+
+    /// IDs of all stored tensors in ascending order
+    pub fn ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.memories.keys().map(|&id| id as usize).collect();
+        ids.sort_unstable();
+        ids
     }
 
     pub fn nearest_neighbor(&self, point: [f64; 2]) -> Option<usize> {
-        // Specify SquaredEuclidean distance metric explicitly
-        let result = self.tree.nearest_one::<SquaredEuclidean>(&point);
-        Some(result.item as usize)
+        self.k_nearest(point, 1).first().map(|&(id, _)| id)
+    }
+
+    /// Up to `k` nearest tensors as `(id, distance)`, closest first
+    pub fn k_nearest(&self, point: [f64; 2], k: usize) -> Vec<(usize, f64)> {
+        if k == 0 || self.is_empty() {
+            return Vec::new();
+        }
+        // Every site holds at least one memory, so k sites always cover k memories
+        let sites = self.tree.nearest_n::<SquaredEuclidean>(&point, k);
+        let mut found = self.expand_sites(sites.iter().map(|n| (n.item, n.distance)));
+        found.truncate(k);
+        found
+    }
+
+    /// All tensors within `radius` of `point` as `(id, distance)`, closest first
+    pub fn within_radius(&self, point: [f64; 2], radius: f64) -> Vec<(usize, f64)> {
+        if radius < 0.0 || self.is_empty() {
+            return Vec::new();
+        }
+        // kiddo excludes points exactly on the boundary; widen the query and filter inclusively
+        let radius_sq = radius * radius;
+        let sites = self.tree.within::<SquaredEuclidean>(&point, radius_sq * (1.0 + 1e-12) + f64::MIN_POSITIVE);
+        self.expand_sites(sites.iter()
+            .filter(|n| n.distance <= radius_sq)
+            .map(|n| (n.item, n.distance)))
+    }
+
+    /// All tensors inside the axis-aligned box `[min, max]` (inclusive)
+    pub fn within_bounds(&self, min: [f64; 2], max: [f64; 2]) -> Vec<usize> {
+        if min[0] > max[0] || min[1] > max[1] {
+            return Vec::new();
+        }
+        let centre = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+        let half_diagonal = ((max[0] - centre[0]).powi(2) + (max[1] - centre[1]).powi(2)).sqrt();
+
+        let mut ids: Vec<usize> = self.within_radius(centre, half_diagonal)
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|&id| {
+                let [x, y] = self.memories[&(id as u64)].position();
+                x >= min[0] && x <= max[0] && y >= min[1] && y <= max[1]
+            })
+            .collect();
+        ids.sort_unstable();
+        ids
     }
 
     pub fn size(&self) -> usize {
-        self.tree.size() as usize
+        self.memories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memories.is_empty()
     }
 
     /// New: Spatial entanglement between positions
@@ -47,4 +166,143 @@ impl CognitiveMap {
             1.0 / distance_sq
         }
     }
+
+    /// Voronoi place field containing `point`
+    pub fn place_field(&self, point: [f64; 2]) -> Option<PlaceRegion> {
+        self.topology.place_field(point)
+    }
+
+    /// IDs whose place fields border the field of memory `id`
+    pub fn adjacent_memories(&self, id: usize) -> Vec<usize> {
+        self.topology.adjacent(id as u64).into_iter().map(|n| n as usize).collect()
+    }
+
+    /// Natural-neighbour interpolation of stored tensor data at `point`
+    pub fn interpolate(&self, point: [f64; 2]) -> Option<Array1<f64>> {
+        self.topology.interpolate(point)
+    }
+
+    /// Population codes of all stored tensors, one row per ID in ascending order
+    pub fn encode_memories(&self, encoder: &dyn SpatialEncoder) -> (Vec<usize>, Array2<f64>) {
+        let ids = self.ids();
+        let mut codes = Array2::zeros((ids.len(), encoder.dimension()));
+        for (row, &id) in ids.iter().enumerate() {
+            codes.row_mut(row).assign(&encoder.encode_tensor(&self.memories[&(id as u64)]));
+        }
+        (ids, codes)
+    }
+
+    /// Memory nearest to the position decoded from a population code
+    pub fn nearest_to_code(&self, encoder: &dyn SpatialEncoder, activity: &Array1<f64>) -> Option<usize> {
+        self.nearest_neighbor(encoder.decode(activity)?)
+    }
+
+    fn site_key(point: [f64; 2]) -> (u64, u64) {
+        // Adding 0.0 folds -0.0 into 0.0 so both map to the same site
+        ((point[0] + 0.0).to_bits(), (point[1] + 0.0).to_bits())
+    }
+
+    fn index(&mut self, id: u64, tensor: &MorphicTensor) {
+        let point = tensor.position();
+        let key = Self::site_key(point);
+        let site = match self.site_at.get(&key) {
+            Some(&site) => site,
+            None => {
+                let site = self.site_counter;
+                self.site_counter += 1;
+                self.tree.add(&point, site);
+                self.site_at.insert(key, site);
+                site
+            }
+        };
+        self.site_members.entry(site).or_default().push(id);
+
+        if let Err(e) = self.topology.insert(id, point, tensor.spatial.data.clone()) {
+            println!("Tensor indexed without place field: {}", e);
+        }
+    }
+
+    fn unindex(&mut self, id: u64, point: [f64; 2]) {
+        let key = Self::site_key(point);
+        if let Some(&site) = self.site_at.get(&key) {
+            let members = self.site_members.entry(site).or_default();
+            members.retain(|&m| m != id);
+            if members.is_empty() {
+                self.site_members.remove(&site);
+                self.site_at.remove(&key);
+                self.tree.remove(&point, site);
+            }
+        }
+        self.topology.remove(id);
+    }
+
+    fn expand_sites(&self, sites: impl Iterator<Item = (u64, f64)>) -> Vec<(usize, f64)> {
+        let mut found: Vec<(usize, f64)> = sites
+            .flat_map(|(site, distance_sq)| {
+                self.site_members.get(&site)
+                    .into_iter()
+                    .flatten()
+                    .map(move |&id| (id as usize, distance_sq.sqrt()))
+            })
+            .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tensor_at(x: f64, y: f64) -> MorphicTensor {
+        let mut tensor = MorphicTensor::void();
+        tensor.spatial.coordinates = [x, y];
+        tensor
+    }
+
+    #[test]
+    fn test_empty_map_queries() {
+        let map = CognitiveMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.nearest_neighbor([0.0, 0.0]), None);
+        assert!(map.k_nearest([0.0, 0.0], 3).is_empty());
+        assert!(map.within_radius([0.0, 0.0], 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_queries_and_stable_ids() {
+        let mut map = CognitiveMap::new();
+        let a = map.insert(&tensor_at(0.0, 0.0));
+        let b = map.insert(&tensor_at(1.0, 0.0));
+        let c = map.insert(&tensor_at(5.0, 5.0));
+
+        assert_eq!(map.nearest_neighbor([0.9, 0.1]), Some(b));
+        let nearest: Vec<usize> = map.k_nearest([0.1, 0.0], 2).iter().map(|&(id, _)| id).collect();
+        assert_eq!(nearest, vec![a, b]);
+        assert_eq!(map.within_radius([0.0, 0.0], 1.5).len(), 2);
+        assert_eq!(map.within_bounds([4.0, 4.0], [6.0, 6.0]), vec![c]);
+
+        assert!(map.remove(a).is_some());
+        assert!(map.get(a).is_none());
+        assert_eq!(map.nearest_neighbor([0.0, 0.0]), Some(b));
+
+        // IDs are not reused after removal
+        let d = map.insert(&tensor_at(0.0, 0.0));
+        assert!(d > c);
+
+        assert!(map.move_to(c, [0.2, 0.2]));
+        assert_eq!(map.within_bounds([4.0, 4.0], [6.0, 6.0]), Vec::<usize>::new());
+        assert_eq!(map.get(c).unwrap().position(), [0.2, 0.2]);
+    }
+
+    #[test]
+    fn test_many_colocated_tensors() {
+        let mut map = CognitiveMap::new();
+        for _ in 0..100 {
+            map.insert(&MorphicTensor::void());
+        }
+        assert_eq!(map.size(), 100);
+        assert_eq!(map.k_nearest([0.0, 0.0], 5).len(), 5);
+        assert_eq!(map.within_radius([0.0, 0.0], 0.0).len(), 100);
+    }
 }
@@ -3,36 +3,505 @@
 
 use crate::core::tensor::MorphicTensor;
 use crate::quantum::state::QuantumState;
+use nalgebra::DVector;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
+/// How a full pool chooses the entry to demote or evict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// Entry whose last store or retrieval is oldest
+    LeastRecentlyUsed,
+    /// Entry that has spent the longest time in its current tier
+    DecoherenceAge,
+}
+
+/// A stored tensor with its bookkeeping on the manager's logical clock
+#[derive(Debug, Clone)]
+pub struct MemoryEntry {
+    pub tensor: MorphicTensor,
+    pub last_access: u64,
+    pub tier_since: u64,
+}
+
+/// Tiered tensor store with void, superposition and classical pools
+///
+/// Superposed tensors are limited to `superposition_capacity`; on overflow the policy's
+/// victim is collapsed and demoted to the classical pool, which in turn drops entries
+/// once it exceeds `classical_capacity`. When opened on a file, every store, removal
+/// and eviction is written through so memories survive restarts. Retrievals only
+/// update recency in memory; it is written on `flush`, on drop, or after
+/// `recency_flush_interval` unsaved retrievals.
 pub struct QuantumMemoryManager {
     pub superposition_capacity: usize,
+    pub classical_capacity: Option<usize>,
+    pub policy: EvictionPolicy,
+    /// Retrievals allowed to accumulate before their recency is written out
+    pub recency_flush_interval: usize,
+    pools: HashMap<QuantumState, HashMap<usize, MemoryEntry>>,
+    next_id: usize,
+    clock: u64,
+    backing_file: Option<PathBuf>,
+    /// Retrievals since the backing file was last written
+    unsaved_reads: Cell<usize>,
 }
 
 impl QuantumMemoryManager {
     pub fn new(capacity: usize) -> Self {
         QuantumMemoryManager {
             superposition_capacity: capacity,
+            classical_capacity: None,
+            policy: EvictionPolicy::LeastRecentlyUsed,
+            recency_flush_interval: 64,
+            pools: HashMap::new(),
+            next_id: 0,
+            clock: 0,
+            backing_file: None,
+            unsaved_reads: Cell::new(0),
+        }
+    }
+
+    /// Open a file-backed store, loading any memories already persisted at `path`
+    pub fn open<P: AsRef<Path>>(path: P, capacity: usize) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let mut manager = Self::new(capacity);
+
+        if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("Cannot read memory store {}: {}", path.display(), e))?;
+            let persisted: PersistedStore = serde_json::from_str(&contents)
+                .map_err(|e| format!("Corrupt memory store {}: {}", path.display(), e))?;
+
+            manager.next_id = persisted.next_id;
+            manager.clock = persisted.clock;
+            for entry in persisted.entries {
+                let tensor = entry.tensor.into_tensor();
+                manager.pool_mut(tensor.quantum_state).insert(entry.id, MemoryEntry {
+                    tensor,
+                    last_access: entry.last_access,
+                    tier_since: entry.tier_since,
+                });
+            }
         }
+
+        manager.backing_file = Some(path);
+        // A store reopened with a smaller capacity demotes down to the new cap
+        let mut demoted = false;
+        while manager.pool_len(QuantumState::Superposition) > manager.superposition_capacity && manager.demote_one() {
+            demoted = true;
+        }
+        if demoted {
+            manager.flush()?;
+        }
+        Ok(manager)
     }
 
     /// Store a tensor in quantum memory
-    pub fn store(&self, tensor: &MorphicTensor) {
+    pub fn store(&mut self, tensor: &MorphicTensor) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        let now = self.tick();
+
         match tensor.quantum_state {
             QuantumState::Void => {
                 println!("Storing void tensor in primordial memory");
             },
             QuantumState::Superposition => {
                 println!("Storing tensor in quantum superposition memory");
+                while self.pool_len(QuantumState::Superposition) >= self.superposition_capacity {
+                    if !self.demote_one() {
+                        break;
+                    }
+                }
             },
             QuantumState::Collapsed => {
                 println!("Storing collapsed tensor in classical memory");
             }
         }
+
+        if tensor.quantum_state == QuantumState::Superposition && self.superposition_capacity == 0 {
+            // No superposition memory at all: the tensor is demoted on arrival
+            let mut collapsed = tensor.clone();
+            collapsed.collapse();
+            self.insert_entry(id, collapsed, now);
+        } else {
+            self.insert_entry(id, tensor.clone(), now);
+        }
+
+        self.enforce_classical_capacity();
+        self.sync();
+        id
+    }
+
+    /// Retrieve a copy of a stored tensor, marking it as recently used
+    pub fn retrieve(&mut self, id: usize) -> Option<MorphicTensor> {
+        let now = self.tick();
+        let entry = self.pools.values_mut().find_map(|pool| pool.get_mut(&id))?;
+        entry.last_access = now;
+        let tensor = entry.tensor.clone();
+        self.unsaved_reads.set(self.unsaved_reads.get() + 1);
+        if self.unsaved_reads.get() >= self.recency_flush_interval {
+            self.sync();
+        }
+        Some(tensor)
+    }
+
+    /// Look at a stored tensor without affecting recency
+    pub fn peek(&self, id: usize) -> Option<&MorphicTensor> {
+        self.pools.values().find_map(|pool| pool.get(&id)).map(|entry| &entry.tensor)
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<MorphicTensor> {
+        let entry = self.pools.values_mut().find_map(|pool| pool.remove(&id))?;
+        self.sync();
+        Some(entry.tensor)
+    }
+
+    /// Tier currently holding memory `id`
+    pub fn tier_of(&self, id: usize) -> Option<QuantumState> {
+        self.pools.iter()
+            .find(|(_, pool)| pool.contains_key(&id))
+            .map(|(&state, _)| state)
+    }
+
+    /// IDs held in one tier, in ascending order
+    pub fn ids_in(&self, state: QuantumState) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.pools.get(&state)
+            .map(|pool| pool.keys().copied().collect())
+            .unwrap_or_default();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn pool_len(&self, state: QuantumState) -> usize {
+        self.pools.get(&state).map(|pool| pool.len()).unwrap_or(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.values().map(|pool| pool.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write the store to its backing file, if any
+    pub fn flush(&self) -> Result<(), String> {
+        let Some(path) = &self.backing_file else {
+            return Ok(());
+        };
+
+        let mut entries: Vec<PersistedEntry> = self.pools.values()
+            .flat_map(|pool| pool.iter())
+            .map(|(&id, entry)| PersistedEntry {
+                id,
+                tensor: PersistedTensor::from_tensor(&entry.tensor),
+                last_access: entry.last_access,
+                tier_since: entry.tier_since,
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.id);
+
+        let store = PersistedStore { next_id: self.next_id, clock: self.clock, entries };
+        let json = serde_json::to_string(&store)
+            .map_err(|e| format!("Cannot serialise memory store: {}", e))?;
+
+        // Write to a sibling file and rename so a crash never leaves a truncated store
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, json)
+            .map_err(|e| format!("Cannot write memory store {}: {}", temp.display(), e))?;
+        fs::rename(&temp, path)
+            .map_err(|e| format!("Cannot replace memory store {}: {}", path.display(), e))?;
+        self.unsaved_reads.set(0);
+        Ok(())
+    }
+
+    fn sync(&self) {
+        if let Err(e) = self.flush() {
+            println!("Memory store not persisted: {}", e);
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn pool_mut(&mut self, state: QuantumState) -> &mut HashMap<usize, MemoryEntry> {
+        self.pools.entry(state).or_default()
+    }
+
+    fn insert_entry(&mut self, id: usize, tensor: MorphicTensor, now: u64) {
+        let state = tensor.quantum_state;
+        self.pool_mut(state).insert(id, MemoryEntry { tensor, last_access: now, tier_since: now });
+    }
+
+    /// Pick the entry of a pool that the eviction policy would give up first
+    fn victim(&self, state: QuantumState) -> Option<usize> {
+        let pool = self.pools.get(&state)?;
+        let key = |entry: &MemoryEntry| match self.policy {
+            EvictionPolicy::LeastRecentlyUsed => entry.last_access,
+            EvictionPolicy::DecoherenceAge => entry.tier_since,
+        };
+        pool.iter()
+            .min_by_key(|(&id, entry)| (key(entry), id))
+            .map(|(&id, _)| id)
+    }
+
+    /// Collapse one superposed tensor into classical memory
+    fn demote_one(&mut self) -> bool {
+        let Some(id) = self.victim(QuantumState::Superposition) else {
+            return false;
+        };
+        let now = self.tick();
+        let mut entry = self.pool_mut(QuantumState::Superposition).remove(&id)
+            .expect("victim is drawn from the pool");
+        entry.tensor.collapse();
+        entry.tier_since = now;
+        println!("Superposition memory full: demoted memory {} to classical memory", id);
+        self.pool_mut(QuantumState::Collapsed).insert(id, entry);
+        true
+    }
+
+    fn enforce_classical_capacity(&mut self) {
+        let Some(capacity) = self.classical_capacity else {
+            return;
+        };
+        while self.pool_len(QuantumState::Collapsed) > capacity {
+            let Some(id) = self.victim(QuantumState::Collapsed) else {
+                break;
+            };
+            self.pool_mut(QuantumState::Collapsed).remove(&id);
+            println!("Classical memory full: evicted memory {}", id);
+        }
     }
+}
+
+impl Drop for QuantumMemoryManager {
+    /// Persist recency left over from retrievals
+    fn drop(&mut self) {
+        if self.unsaved_reads.get() > 0 {
+            self.sync();
+        }
+    }
+}
+
+/// JSON has no NaN or infinities, so non-finite values are written as strings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(untagged)]
+enum PersistedFloat {
+    Finite(f64),
+    Special(NonFinite),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum NonFinite {
+    NaN,
+    #[serde(rename = "inf")]
+    Infinity,
+    #[serde(rename = "-inf")]
+    NegativeInfinity,
+}
+
+impl PersistedFloat {
+    fn encode(value: f64) -> Self {
+        if value.is_nan() {
+            PersistedFloat::Special(NonFinite::NaN)
+        } else if value == f64::INFINITY {
+            PersistedFloat::Special(NonFinite::Infinity)
+        } else if value == f64::NEG_INFINITY {
+            PersistedFloat::Special(NonFinite::NegativeInfinity)
+        } else {
+            PersistedFloat::Finite(value)
+        }
+    }
+
+    fn decode(self) -> f64 {
+        match self {
+            PersistedFloat::Finite(value) => value,
+            PersistedFloat::Special(NonFinite::NaN) => f64::NAN,
+            PersistedFloat::Special(NonFinite::Infinity) => f64::INFINITY,
+            PersistedFloat::Special(NonFinite::NegativeInfinity) => f64::NEG_INFINITY,
+        }
+    }
+
+    fn encode_all<'a>(values: impl IntoIterator<Item = &'a f64>) -> Vec<Self> {
+        values.into_iter().map(|&v| Self::encode(v)).collect()
+    }
+
+    fn decode_all(values: Vec<Self>) -> Vec<f64> {
+        values.into_iter().map(Self::decode).collect()
+    }
+}
+
+/// On-disk form of a tensor (plain vectors, independent of ndarray/nalgebra layouts)
+#[derive(Serialize, Deserialize)]
+struct PersistedTensor {
+    coordinates: [PersistedFloat; 2],
+    data: Option<Vec<PersistedFloat>>,
+    versions: Vec<usize>,
+    connections: Vec<usize>,
+    strength: PersistedFloat,
+    potential: Vec<PersistedFloat>,
+    observer: [PersistedFloat; 2],
+    quantum_state: QuantumState,
+}
+
+impl PersistedTensor {
+    fn from_tensor(tensor: &MorphicTensor) -> Self {
+        PersistedTensor {
+            coordinates: tensor.spatial.coordinates.map(PersistedFloat::encode),
+            data: tensor.spatial.data.as_ref().map(|d| PersistedFloat::encode_all(d.iter())),
+            versions: tensor.temporal.versions.clone(),
+            connections: tensor.entanglement.connections.clone(),
+            strength: PersistedFloat::encode(tensor.entanglement.strength),
+            potential: PersistedFloat::encode_all(tensor.potential.values.iter()),
+            observer: tensor.observer.weights.map(PersistedFloat::encode),
+            quantum_state: tensor.quantum_state,
+        }
+    }
+
+    fn into_tensor(self) -> MorphicTensor {
+        let mut tensor = MorphicTensor::void();
+        tensor.spatial.coordinates = self.coordinates.map(PersistedFloat::decode);
+        tensor.spatial.data = self.data.map(|d| Array1::from_vec(PersistedFloat::decode_all(d)));
+        tensor.temporal.versions = self.versions;
+        tensor.entanglement.connections = self.connections;
+        tensor.entanglement.strength = self.strength.decode();
+        tensor.potential.values = DVector::from_vec(PersistedFloat::decode_all(self.potential));
+        tensor.observer.weights = self.observer.map(PersistedFloat::decode);
+        tensor.quantum_state = self.quantum_state;
+        tensor
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    id: usize,
+    tensor: PersistedTensor,
+    last_access: u64,
+    tier_since: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedStore {
+    next_id: usize,
+    clock: u64,
+    entries: Vec<PersistedEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn superposed(value: f64) -> MorphicTensor {
+        let mut tensor = MorphicTensor::from_data(Array1::from_vec(vec![value]));
+        tensor.quantum_state = QuantumState::Superposition;
+        tensor
+    }
+
+    #[test]
+    fn test_overflow_demotes_least_recently_used() {
+        let mut memory = QuantumMemoryManager::new(2);
+        let a = memory.store(&superposed(0.4));
+        let b = memory.store(&superposed(1.6));
+        memory.retrieve(a);
+        let c = memory.store(&superposed(2.2));
+
+        assert_eq!(memory.ids_in(QuantumState::Superposition), vec![a, c]);
+        assert_eq!(memory.tier_of(b), Some(QuantumState::Collapsed));
+        // Demotion collapses the tensor's data as well as its state
+        assert_eq!(memory.peek(b).unwrap().to_array()[0], 2.0);
+    }
+
+    #[test]
+    fn test_decoherence_age_and_classical_eviction() {
+        let mut memory = QuantumMemoryManager::new(1);
+        memory.policy = EvictionPolicy::DecoherenceAge;
+        memory.classical_capacity = Some(1);
+
+        let a = memory.store(&superposed(1.0));
+        memory.retrieve(a);
+        let b = memory.store(&superposed(2.0));
+        let c = memory.store(&superposed(3.0));
+
+        // a was demoted first, then dropped when b was demoted after it
+        assert_eq!(memory.tier_of(a), None);
+        assert_eq!(memory.tier_of(b), Some(QuantumState::Collapsed));
+        assert_eq!(memory.tier_of(c), Some(QuantumState::Superposition));
+    }
+
+    #[test]
+    fn test_memories_survive_reopen() {
+        let path = std::env::temp_dir().join(format!("morph_memory_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let id = {
+            let mut memory = QuantumMemoryManager::open(&path, 4).unwrap();
+            memory.store(&MorphicTensor::void());
+            memory.store(&superposed(0.5))
+        };
+
+        let mut reopened = QuantumMemoryManager::open(&path, 4).unwrap();
+        assert_eq!(reopened.len(), 2);
+        let restored = reopened.retrieve(id).unwrap();
+        assert_eq!(restored.quantum_state, QuantumState::Superposition);
+        assert_eq!(restored.to_array().to_vec(), vec![0.5]);
+        assert!(reopened.store(&MorphicTensor::void()) > id);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_non_finite_values_round_trip() {
+        let path = std::env::temp_dir().join(format!("morph_memory_nan_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut tensor = MorphicTensor::from_data(Array1::from_vec(vec![1.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY]));
+        tensor.entanglement.strength = f64::NAN;
+        let id = QuantumMemoryManager::open(&path, 4).unwrap().store(&tensor);
+
+        let reopened = QuantumMemoryManager::open(&path, 4).unwrap();
+        let restored = reopened.peek(id).unwrap();
+        let data = restored.to_array();
+        assert_eq!(data[0], 1.5);
+        assert!(data[1].is_nan());
+        assert_eq!(data[2], f64::INFINITY);
+        assert_eq!(data[3], f64::NEG_INFINITY);
+        assert!(restored.entanglement.strength.is_nan());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reopen_persists_recency_and_enforces_smaller_capacity() {
+        let path = std::env::temp_dir().join(format!("morph_memory_cap_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let (a, b, c) = {
+            let mut memory = QuantumMemoryManager::open(&path, 3).unwrap();
+            let a = memory.store(&superposed(1.0));
+            let b = memory.store(&superposed(2.0));
+            let c = memory.store(&superposed(3.0));
+            // Touching a makes b the least recently used once reopened
+            let written = fs::read_to_string(&path).unwrap();
+            memory.retrieve(a);
+            // The read stays in memory until the store is flushed or dropped
+            assert_eq!(fs::read_to_string(&path).unwrap(), written);
+            (a, b, c)
+        };
+
+        let reopened = QuantumMemoryManager::open(&path, 2).unwrap();
+        assert_eq!(reopened.ids_in(QuantumState::Superposition), vec![a, c]);
+        assert_eq!(reopened.tier_of(b), Some(QuantumState::Collapsed));
+        // The demotion was written through as well
+        let again = QuantumMemoryManager::open(&path, 2).unwrap();
+        assert_eq!(again.tier_of(b), Some(QuantumState::Collapsed));
 
-    /// Retrieve from memory (placeholder)
-    pub fn retrieve(&self, id: usize) -> Option<MorphicTensor> {
-        println!("Retrieving memory item {}", id);
-        None
+        fs::remove_file(&path).unwrap();
     }
 }
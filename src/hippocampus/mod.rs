@@ -1,5 +1,9 @@
 //! Hippocampal spatial indexing and memory mapping
 pub mod index;
 pub mod pattern_completion;
+pub mod hopfield;
 pub mod oscillation;
 pub mod memory;
+pub mod topology;
+pub mod consolidation;
+pub mod encoding;
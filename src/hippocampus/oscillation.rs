@@ -1,10 +1,120 @@
 // Theta-gamma oscillation simulation
 #![allow(dead_code)]
 
+use std::f64::consts::TAU;
+
+/// Sampled theta-gamma waveforms
+#[derive(Debug, Clone)]
+pub struct OscillationTrace {
+    pub sample_rate: f64,
+    pub times: Vec<f64>,
+    pub theta: Vec<f64>,
+    pub gamma: Vec<f64>,
+    /// Instantaneous theta phase in [0, 2π)
+    pub theta_phase: Vec<f64>,
+    /// Instantaneous gamma amplitude (the envelope modulated by theta phase)
+    pub gamma_envelope: Vec<f64>,
+}
+
+impl OscillationTrace {
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Local field potential: theta plus nested gamma
+    pub fn signal(&self) -> Vec<f64> {
+        self.theta.iter().zip(&self.gamma).map(|(t, g)| t + g).collect()
+    }
+
+    /// Phase-amplitude modulation index of gamma by theta (see [`modulation_index`])
+    pub fn modulation_index(&self, bins: usize) -> f64 {
+        modulation_index(&self.theta_phase, &self.gamma_envelope, bins)
+    }
+}
+
+/// Tort et al. modulation index of `amplitudes` by `phases`
+///
+/// Amplitudes are averaged per phase bin and normalised into a distribution P;
+/// MI = (log N − H(P)) / log N, so 0 means no coupling and 1 means all amplitude
+/// falls in one bin. Empty bins are ignored.
+pub fn modulation_index(phases: &[f64], amplitudes: &[f64], bins: usize) -> f64 {
+    if bins < 2 {
+        return 0.0;
+    }
+    let mut sums = vec![0.0; bins];
+    let mut counts = vec![0usize; bins];
+    for (&phase, &amplitude) in phases.iter().zip(amplitudes) {
+        let bin = ((phase.rem_euclid(TAU) / TAU) * bins as f64) as usize;
+        let bin = bin.min(bins - 1);
+        sums[bin] += amplitude;
+        counts[bin] += 1;
+    }
+
+    let means: Vec<f64> = sums.iter().zip(&counts)
+        .filter(|(_, &count)| count > 0)
+        .map(|(sum, &count)| sum / count as f64)
+        .collect();
+    let total: f64 = means.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let entropy: f64 = means.iter()
+        .map(|m| m / total)
+        .filter(|&p| p > 0.0)
+        .map(|p| -p * p.ln())
+        .sum();
+    let max_entropy = (bins as f64).ln();
+    ((max_entropy - entropy) / max_entropy).max(0.0)
+}
+
+/// Circular place field traversed along a heading
+#[derive(Debug, Clone, Copy)]
+pub struct PlaceField {
+    pub center: [f64; 2],
+    pub radius: f64,
+}
+
+impl PlaceField {
+    pub fn new(center: [f64; 2], radius: f64) -> Self {
+        PlaceField { center, radius }
+    }
+
+    /// Fraction of the field crossed so far (0 at entry, 1 at exit) when moving along
+    /// `heading`, or `None` outside the field
+    pub fn progress(&self, position: [f64; 2], heading: [f64; 2]) -> Option<f64> {
+        let dx = position[0] - self.center[0];
+        let dy = position[1] - self.center[1];
+        if self.radius <= 0.0 || dx * dx + dy * dy > self.radius * self.radius {
+            return None;
+        }
+        let norm = (heading[0] * heading[0] + heading[1] * heading[1]).sqrt();
+        if norm == 0.0 {
+            return Some(0.5);
+        }
+        let along = (dx * heading[0] + dy * heading[1]) / norm;
+        Some(((along + self.radius) / (2.0 * self.radius)).clamp(0.0, 1.0))
+    }
+}
+
 pub struct Oscillator {
     pub theta_frequency: f64,
     pub gamma_frequency: f64,
     pub amplitude: f64,
+    /// Peak gamma amplitude
+    pub gamma_amplitude: f64,
+    /// Samples per second
+    pub sample_rate: f64,
+    /// Depth of gamma modulation by theta phase (0 = none, 1 = full)
+    pub coupling_strength: f64,
+    /// Theta phase at which gamma bursts peak
+    pub preferred_phase: f64,
+    /// Total theta phase advanced while crossing a place field
+    pub precession_range: f64,
 }
 
 impl Oscillator {
@@ -13,18 +123,136 @@ impl Oscillator {
             theta_frequency: theta,
             gamma_frequency: gamma,
             amplitude,
+            gamma_amplitude: amplitude / 4.0,
+            sample_rate: 1000.0,
+            coupling_strength: 0.8,
+            preferred_phase: std::f64::consts::PI,
+            precession_range: 1.5 * std::f64::consts::PI,
+        }
+    }
+
+    /// Gamma envelope at a given theta phase
+    pub fn gamma_envelope(&self, theta_phase: f64) -> f64 {
+        let chi = self.coupling_strength.clamp(0.0, 1.0);
+        self.gamma_amplitude * ((1.0 - chi) + chi * (1.0 + (theta_phase - self.preferred_phase).cos()) / 2.0)
+    }
+
+    /// Sample `duration` seconds of nested theta-gamma activity
+    pub fn simulate(&self, duration: f64) -> OscillationTrace {
+        let samples = if self.sample_rate > 0.0 && duration > 0.0 {
+            (duration * self.sample_rate).round() as usize
+        } else {
+            0
+        };
+        let mut trace = OscillationTrace {
+            sample_rate: self.sample_rate,
+            times: Vec::with_capacity(samples),
+            theta: Vec::with_capacity(samples),
+            gamma: Vec::with_capacity(samples),
+            theta_phase: Vec::with_capacity(samples),
+            gamma_envelope: Vec::with_capacity(samples),
+        };
+
+        for n in 0..samples {
+            let t = n as f64 / self.sample_rate;
+            let phase = (TAU * self.theta_frequency * t).rem_euclid(TAU);
+            let envelope = self.gamma_envelope(phase);
+            trace.times.push(t);
+            trace.theta.push(self.amplitude * phase.cos());
+            trace.gamma.push(envelope * (TAU * self.gamma_frequency * t).cos());
+            trace.theta_phase.push(phase);
+            trace.gamma_envelope.push(envelope);
         }
+        trace
     }
 
     /// Simulate one cycle of theta-gamma oscillation
-    pub fn simulate_cycle(&self) {
+    pub fn simulate_cycle(&self) -> OscillationTrace {
         println!("Simulating θ-γ oscillation: θ={}Hz, γ={}Hz",
                  self.theta_frequency, self.gamma_frequency);
-        // Actual oscillation simulation would go here
+        if self.theta_frequency <= 0.0 {
+            return self.simulate(0.0);
+        }
+        self.simulate(1.0 / self.theta_frequency)
+    }
+
+    /// Number of gamma cycles nested in one theta cycle
+    pub fn gamma_slots(&self) -> usize {
+        if self.theta_frequency <= 0.0 {
+            return 0;
+        }
+        (self.gamma_frequency / self.theta_frequency).floor() as usize
+    }
+
+    /// Theta phase at which a place cell fires at `position` (phase precession)
+    ///
+    /// The firing phase starts at `preferred_phase + precession_range / 2` on entering
+    /// the field and advances linearly to `preferred_phase - precession_range / 2` on
+    /// leaving it, wrapped into [0, 2π).
+    pub fn precession_phase(&self, field: &PlaceField, position: [f64; 2], heading: [f64; 2]) -> Option<f64> {
+        let progress = field.progress(position, heading)?;
+        let phase = self.preferred_phase + self.precession_range * (0.5 - progress);
+        Some(phase.rem_euclid(TAU))
+    }
+
+    /// Gamma cycle within the theta cycle that a theta phase falls in
+    pub fn gamma_slot(&self, theta_phase: f64) -> usize {
+        let slots = self.gamma_slots();
+        if slots == 0 {
+            return 0;
+        }
+        (((theta_phase.rem_euclid(TAU) / TAU) * slots as f64) as usize).min(slots - 1)
     }
 
     /// Entangle oscillation with spatial position
-    pub fn entangle_with_position(&self, position: [f64; 2]) {
-        println!("Entangling oscillation with position: {:?}", position);
+    ///
+    /// Returns the precessed firing phase for `position` within `field`, or `None`
+    /// when the position lies outside it.
+    pub fn entangle_with_position(&self, position: [f64; 2], field: &PlaceField, heading: [f64; 2]) -> Option<f64> {
+        let phase = self.precession_phase(field, position, heading);
+        if let Some(phase) = phase {
+            println!("Entangling oscillation with position {:?}: phase {:.3} rad, γ slot {}",
+                     position, phase, self.gamma_slot(phase));
+        }
+        phase
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_coupling_strength_drives_modulation_index() {
+        let mut oscillator = Oscillator::new(8.0, 40.0, 1.0);
+        let cycle = oscillator.simulate_cycle();
+        assert_eq!(cycle.len(), 125);
+        let coupled = oscillator.simulate(2.0).modulation_index(18);
+
+        oscillator.coupling_strength = 0.0;
+        let uncoupled = oscillator.simulate(2.0).modulation_index(18);
+        assert!(uncoupled < 1e-12);
+        assert!(coupled > 0.01);
+
+        // Gamma peaks at the preferred theta phase
+        oscillator.coupling_strength = 1.0;
+        assert_relative_eq!(oscillator.gamma_envelope(oscillator.preferred_phase), 0.25);
+        assert_relative_eq!(oscillator.gamma_envelope(0.0), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_phase_precesses_across_place_field() {
+        let oscillator = Oscillator::new(8.0, 40.0, 1.0);
+        let field = PlaceField::new([0.0, 0.0], 1.0);
+        let heading = [1.0, 0.0];
+
+        let phases: Vec<f64> = [-0.9, -0.3, 0.3, 0.9].iter()
+            .map(|&x| oscillator.precession_phase(&field, [x, 0.0], heading).unwrap())
+            .collect();
+        assert!(phases.windows(2).all(|w| w[1] < w[0]));
+        assert!(oscillator.precession_phase(&field, [2.0, 0.0], heading).is_none());
+        assert_eq!(oscillator.gamma_slots(), 5);
+        assert_eq!(oscillator.gamma_slot(TAU - 1e-9), 4);
     }
 }
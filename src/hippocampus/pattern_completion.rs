@@ -1,33 +1,174 @@
 // Pattern completion algorithms for cognitive mapping
 #![allow(dead_code)]
 
+use ndarray::Array1;
+use crate::core::tensor::MorphicTensor;
+use crate::hippocampus::hopfield::{HopfieldNetwork, HopfieldRecall};
 use crate::hippocampus::index::CognitiveMap;
+use crate::hippocampus::oscillation::{Oscillator, PlaceField};
+
+/// A memory's place in the current theta cycle
+#[derive(Debug, Clone, Copy)]
+pub struct ThetaSlot {
+    pub id: usize,
+    /// Precessed firing phase in [0, 2π)
+    pub phase: f64,
+    pub gamma_cycle: usize,
+}
 
 pub struct PatternCompleter {
     pub cognitive_map: CognitiveMap,
     pub completion_threshold: f64,
+    pub network: HopfieldNetwork,
+    /// Cognitive-map ID of each attractor, in network order
+    pub attractor_ids: Vec<usize>,
 }
 
 impl PatternCompleter {
+    /// Build a completer whose attractors are the data vectors stored in the map
+    ///
+    /// Tensors without data, or whose dimension differs from the first stored
+    /// pattern, are indexed spatially but not stored as attractors.
     pub fn new(cognitive_map: CognitiveMap, threshold: f64) -> Self {
-        PatternCompleter {
+        let mut completer = PatternCompleter {
             cognitive_map,
             completion_threshold: threshold,
+            network: HopfieldNetwork::new(1.0),
+            attractor_ids: Vec::new(),
+        };
+        for id in completer.cognitive_map.ids() {
+            completer.store_attractor(id);
+        }
+        completer
+    }
+
+    /// Index a tensor in the map and, if it carries data, store it as an attractor
+    pub fn remember(&mut self, tensor: &MorphicTensor) -> usize {
+        let id = self.cognitive_map.insert(tensor);
+        self.store_attractor(id);
+        id
+    }
+
+    /// Remove a memory from the map along with its attractor
+    pub fn forget(&mut self, id: usize) -> Option<MorphicTensor> {
+        let tensor = self.cognitive_map.remove(id)?;
+        self.prune_attractors();
+        Some(tensor)
+    }
+
+    /// Drop attractors whose memory has left the cognitive map; returns how many
+    ///
+    /// Needed after removing tensors through `cognitive_map` directly.
+    pub fn prune_attractors(&mut self) -> usize {
+        let before = self.attractor_ids.len();
+        let mut index = 0;
+        while index < self.attractor_ids.len() {
+            if self.cognitive_map.contains(self.attractor_ids[index]) {
+                index += 1;
+            } else {
+                self.attractor_ids.remove(index);
+                self.network.patterns.remove(index);
+            }
+        }
+        before - self.attractor_ids.len()
+    }
+
+    fn store_attractor(&mut self, id: usize) -> bool {
+        let Some(data) = self.cognitive_map.get(id).and_then(|t| t.spatial.data.clone()) else {
+            return false;
+        };
+        if self.network.store(data).is_ok() {
+            self.attractor_ids.push(id);
+            true
+        } else {
+            false
         }
     }
 
     /// Attempt to complete a pattern from partial input
+    ///
+    /// The memory nearest to `partial_point` cues a recall, and its attractor is
+    /// returned only if the recall clears `completion_threshold`. Memories without
+    /// data cannot be assessed and complete nothing.
     pub fn complete_pattern(&self, partial_point: [f64; 2]) -> Option<usize> {
         let nearest = self.cognitive_map.nearest_neighbor(partial_point)?;
+        let cue = self.cognitive_map.get(nearest)?.spatial.data.clone()?;
+        self.complete(&cue).map(|(id, _)| id)
+    }
+
+    /// Content-addressable recall of a full data vector from a partial or noisy cue
+    ///
+    /// Unknown cue entries may be given as NaN. Returns the cognitive-map ID of the
+    /// winning attractor with the recall, or `None` if the recall's confidence falls
+    /// below `completion_threshold` or the winner's memory has left the map.
+    pub fn complete(&self, cue: &Array1<f64>) -> Option<(usize, HopfieldRecall)> {
+        let recall = self.network.recall(cue)?;
+        if recall.confidence < self.completion_threshold {
+            return None;
+        }
+        let id = self.attractor_ids[recall.attractor];
+        self.cognitive_map.contains(id).then_some((id, recall))
+    }
 
-        // In a real implementation, we would use a more sophisticated pattern matching algorithm
-        // This is a placeholder for the actual pattern completion logic
-        Some(nearest)
+    /// Theta sequence of the memories whose place fields contain `position`
+    ///
+    /// Each stored tensor gets a circular place field of `field_radius` around its
+    /// position. Fields are ordered by their precessed firing phase, so memories
+    /// already passed fire early in the theta cycle and upcoming ones late, each in
+    /// its own gamma slot.
+    pub fn theta_gamma_coupling(&self, oscillator: &Oscillator, position: [f64; 2], heading: [f64; 2], field_radius: f64) -> Vec<ThetaSlot> {
+        let mut sequence: Vec<ThetaSlot> = self.cognitive_map.within_radius(position, field_radius)
+            .into_iter()
+            .filter_map(|(id, _)| {
+                let field = PlaceField::new(self.cognitive_map.get(id)?.position(), field_radius);
+                let phase = oscillator.precession_phase(&field, position, heading)?;
+                Some(ThetaSlot { id, phase, gamma_cycle: oscillator.gamma_slot(phase) })
+            })
+            .collect();
+        sequence.sort_by(|a, b| a.phase.total_cmp(&b.phase).then(a.id.cmp(&b.id)));
+        println!("Theta-gamma coupling at {:?}: {} memories across {} γ slots",
+                 position, sequence.len(), oscillator.gamma_slots());
+        sequence
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn memory(data: Array1<f64>, position: [f64; 2]) -> MorphicTensor {
+        let mut tensor = MorphicTensor::from_data(data);
+        tensor.spatial.coordinates = position;
+        tensor
+    }
+
+    #[test]
+    fn test_complete_pattern_respects_threshold() {
+        let mut completer = PatternCompleter::new(CognitiveMap::new(), 0.9);
+        let distinct = completer.remember(&memory(array![1.0, 1.0, -1.0, -1.0, 1.0, -1.0], [0.0, 0.0]));
+        completer.remember(&memory(array![-1.0, 1.0, 1.0, -1.0, -1.0, 1.0], [5.0, 5.0]));
+        assert_eq!(completer.complete_pattern([0.2, -0.1]), Some(distinct));
+
+        // Two nearly identical engrams split the attention, so neither is confident
+        completer.remember(&memory(array![3.0, 3.0, 3.0, 3.0, 3.0, 3.0], [10.0, 0.0]));
+        completer.remember(&memory(array![3.0, 3.0, 3.0, 3.0, 3.0, 2.9], [10.5, 0.0]));
+        assert_eq!(completer.complete_pattern([10.1, 0.0]), None);
+
+        // A memory removed behind the completer's back is never recalled
+        let other = completer.remember(&memory(array![1.0, -1.0, 1.0, 1.0, -1.0, -1.0], [0.0, 5.0]));
+        let cue = array![1.0, 1.0, -1.0, -1.0, 1.0, -1.0];
+        assert!(completer.complete(&cue).is_some_and(|(id, _)| id == distinct));
+        completer.cognitive_map.remove(distinct);
+        assert!(completer.complete(&cue).is_none());
+        assert_eq!(completer.prune_attractors(), 1);
+        assert_eq!(completer.attractor_ids.len(), completer.network.patterns.len());
+        assert!(completer.complete(&array![1.0, -1.0, 1.0, 1.0, -1.0, -1.0]).is_some_and(|(id, _)| id == other));
+        assert!(completer.forget(other).is_some());
+        assert!(!completer.attractor_ids.contains(&other));
 
-    /// Theta-gamma coupling simulation placeholder
-    pub fn theta_gamma_coupling(&self, frequency: f64) {
-        println!("Simulating theta-gamma coupling at {} Hz", frequency);
-        // Actual oscillation simulation would go here
+        let mut lonely = PatternCompleter::new(CognitiveMap::new(), 0.0);
+        lonely.remember(&MorphicTensor::void());
+        assert_eq!(lonely.complete_pattern([0.0, 0.0]), None);
     }
 }
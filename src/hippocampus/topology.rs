@@ -0,0 +1,295 @@
+// Delaunay/Voronoi place-cell topology for the cognitive map
+#![allow(dead_code)]
+
+use ndarray::Array1;
+use spade::{DelaunayTriangulation, HasPosition, Point2, Triangulation};
+use std::collections::HashMap;
+
+/// Triangulation vertex holding every memory stored at one position
+#[derive(Debug, Clone)]
+pub struct PlaceVertex {
+    pub position: Point2<f64>,
+    pub members: Vec<(u64, Option<Array1<f64>>)>,
+}
+
+impl HasPosition for PlaceVertex {
+    type Scalar = f64;
+
+    fn position(&self) -> Point2<f64> {
+        self.position
+    }
+}
+
+impl PlaceVertex {
+    /// Mean of the data vectors stored at this vertex
+    pub fn value(&self) -> Option<Array1<f64>> {
+        let mut values = self.members.iter().filter_map(|(_, v)| v.as_ref());
+        let first = values.next()?;
+        let mut sum = first.clone();
+        let mut count = 1.0;
+        for value in values.filter(|v| v.len() == first.len()) {
+            sum += value;
+            count += 1.0;
+        }
+        Some(sum / count)
+    }
+}
+
+/// Voronoi region (place field) around one stored position
+#[derive(Debug, Clone)]
+pub struct PlaceRegion {
+    pub center: [f64; 2],
+    pub ids: Vec<u64>,
+    /// Finite Voronoi vertices in counter-clockwise order
+    pub polygon: Vec<[f64; 2]>,
+    /// False when the site lies on the convex hull and its region is unbounded
+    pub bounded: bool,
+}
+
+impl PlaceRegion {
+    /// Area of a bounded region (shoelace formula)
+    pub fn area(&self) -> Option<f64> {
+        if !self.bounded || self.polygon.len() < 3 {
+            return None;
+        }
+        let n = self.polygon.len();
+        let twice_area: f64 = (0..n)
+            .map(|i| {
+                let [x0, y0] = self.polygon[i];
+                let [x1, y1] = self.polygon[(i + 1) % n];
+                x0 * y1 - x1 * y0
+            })
+            .sum();
+        Some(twice_area.abs() / 2.0)
+    }
+}
+
+/// Delaunay triangulation of stored memory positions
+///
+/// Co-located memories share one vertex. Its Voronoi dual partitions the plane into
+/// place fields, and natural-neighbour coordinates interpolate between stored values.
+pub struct PlaceCellTopology {
+    pub triangulation: DelaunayTriangulation<PlaceVertex>,
+    positions: HashMap<u64, [f64; 2]>,
+}
+
+impl Default for PlaceCellTopology {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlaceCellTopology {
+    pub fn new() -> Self {
+        PlaceCellTopology {
+            triangulation: DelaunayTriangulation::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Add a memory at `position`, joining any vertex already at that point
+    pub fn insert(&mut self, id: u64, position: [f64; 2], value: Option<Array1<f64>>) -> Result<(), String> {
+        let point = Point2::new(position[0], position[1]);
+        let existing = self.triangulation.locate_vertex(point).map(|v| v.fix());
+
+        match existing {
+            Some(handle) => {
+                self.triangulation.vertex_data_mut(handle).members.push((id, value));
+            }
+            None => {
+                let vertex = PlaceVertex { position: point, members: vec![(id, value)] };
+                self.triangulation.insert(vertex)
+                    .map_err(|e| format!("Cannot place memory {} at {:?}: {:?}", id, position, e))?;
+            }
+        }
+
+        self.positions.insert(id, position);
+        Ok(())
+    }
+
+    /// Remove a memory, dropping its site once no other memory shares it
+    pub fn remove(&mut self, id: u64) -> bool {
+        let Some([x, y]) = self.positions.remove(&id) else {
+            return false;
+        };
+        let Some(handle) = self.triangulation.locate_vertex(Point2::new(x, y)).map(|v| v.fix()) else {
+            return false;
+        };
+
+        let members = &mut self.triangulation.vertex_data_mut(handle).members;
+        members.retain(|(member, _)| *member != id);
+        if members.is_empty() {
+            self.triangulation.remove(handle);
+        }
+        true
+    }
+
+    /// Number of distinct place-field sites
+    pub fn num_sites(&self) -> usize {
+        self.triangulation.num_vertices()
+    }
+
+    pub fn position_of(&self, id: u64) -> Option<[f64; 2]> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Voronoi region of the site at exactly `position`
+    pub fn region_at(&self, position: [f64; 2]) -> Option<PlaceRegion> {
+        let vertex = self.triangulation.locate_vertex(Point2::new(position[0], position[1]))?;
+        let mut polygon = Vec::new();
+        let mut bounded = true;
+
+        for edge in vertex.out_edges() {
+            match edge.face().as_inner() {
+                Some(face) => {
+                    let c = face.circumcenter();
+                    polygon.push([c.x, c.y]);
+                }
+                None => bounded = false,
+            }
+        }
+
+        Some(PlaceRegion {
+            center: position,
+            ids: vertex.data().members.iter().map(|(id, _)| *id).collect(),
+            polygon,
+            // An isolated site has no edges and therefore no finite region
+            bounded: bounded && vertex.out_edge().is_some(),
+        })
+    }
+
+    /// Place field that `point` falls in, i.e. the Voronoi region of its nearest site
+    pub fn place_field(&self, point: [f64; 2]) -> Option<PlaceRegion> {
+        let nearest = self.triangulation.nearest_neighbor(Point2::new(point[0], point[1]))?;
+        let p = nearest.position();
+        self.region_at([p.x, p.y])
+    }
+
+    /// Place field of the site holding memory `id`
+    pub fn region_of(&self, id: u64) -> Option<PlaceRegion> {
+        self.region_at(self.position_of(id)?)
+    }
+
+    /// All place fields of the map
+    pub fn regions(&self) -> Vec<PlaceRegion> {
+        self.triangulation.vertices()
+            .filter_map(|v| {
+                let p = v.position();
+                self.region_at([p.x, p.y])
+            })
+            .collect()
+    }
+
+    /// IDs stored at Delaunay-adjacent sites (place fields sharing a Voronoi edge)
+    pub fn adjacent(&self, id: u64) -> Vec<u64> {
+        let Some([x, y]) = self.position_of(id) else {
+            return Vec::new();
+        };
+        let Some(vertex) = self.triangulation.locate_vertex(Point2::new(x, y)) else {
+            return Vec::new();
+        };
+
+        vertex.out_edges()
+            .flat_map(|edge| edge.to().data().members.iter().map(|(id, _)| *id).collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// Natural-neighbour (Sibson) coordinates of `point`, split evenly over co-located IDs
+    ///
+    /// Empty when `point` lies outside the convex hull of the stored positions.
+    pub fn natural_neighbor_weights(&self, point: [f64; 2]) -> Vec<(u64, f64)> {
+        let mut weights = Vec::new();
+        self.triangulation.natural_neighbor()
+            .get_weights(Point2::new(point[0], point[1]), &mut weights);
+
+        weights.into_iter()
+            .flat_map(|(handle, weight)| {
+                let vertex = self.triangulation.vertex(handle);
+                let members = &vertex.data().members;
+                let share = weight / members.len() as f64;
+                members.iter().map(|(id, _)| (*id, share)).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Natural-neighbour interpolation of stored data vectors at `point`
+    ///
+    /// Sites without data (or with a different dimension than the first contributing
+    /// site) are skipped and the remaining weights renormalised.
+    pub fn interpolate(&self, point: [f64; 2]) -> Option<Array1<f64>> {
+        let mut weights = Vec::new();
+        self.triangulation.natural_neighbor()
+            .get_weights(Point2::new(point[0], point[1]), &mut weights);
+
+        let mut result: Option<Array1<f64>> = None;
+        let mut total_weight = 0.0;
+        for (handle, weight) in weights {
+            let Some(value) = self.triangulation.vertex(handle).data().value() else {
+                continue;
+            };
+            match &mut result {
+                Some(acc) if acc.len() == value.len() => acc.scaled_add(weight, &value),
+                Some(_) => continue,
+                None => result = Some(value * weight),
+            }
+            total_weight += weight;
+        }
+
+        result.filter(|_| total_weight > 0.0).map(|acc| acc / total_weight)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    /// 3×3 unit grid storing the linear field f(x, y) = [x + 2y]
+    fn grid_topology() -> PlaceCellTopology {
+        let mut topology = PlaceCellTopology::new();
+        for k in 0..9u64 {
+            let (x, y) = ((k % 3) as f64, (k / 3) as f64);
+            topology.insert(k, [x, y], Some(array![x + 2.0 * y])).unwrap();
+        }
+        topology
+    }
+
+    #[test]
+    fn test_natural_neighbor_reproduces_linear_field() {
+        let topology = grid_topology();
+        let value = topology.interpolate([0.7, 1.3]).unwrap();
+        assert_relative_eq!(value[0], 0.7 + 2.6, epsilon = 1e-9);
+        assert!(topology.interpolate([5.0, 5.0]).is_none());
+
+        let total: f64 = topology.natural_neighbor_weights([1.2, 0.4]).iter().map(|(_, w)| w).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_voronoi_regions_and_adjacency() {
+        let topology = grid_topology();
+
+        // The centre site owns the unit square around it
+        let centre = topology.region_of(4).unwrap();
+        assert!(centre.bounded);
+        assert_relative_eq!(centre.area().unwrap(), 1.0, epsilon = 1e-9);
+        assert!(!topology.region_of(0).unwrap().bounded);
+
+        assert_eq!(topology.place_field([1.2, 0.9]).unwrap().ids, vec![4]);
+        let neighbours = topology.adjacent(4);
+        for id in [1, 3, 5, 7] {
+            assert!(neighbours.contains(&id));
+        }
+    }
+
+    #[test]
+    fn test_colocated_memories_share_a_site() {
+        let mut topology = grid_topology();
+        topology.insert(42, [1.0, 1.0], Some(array![5.0])).unwrap();
+        assert_eq!(topology.num_sites(), 9);
+        assert_eq!(topology.region_of(42).unwrap().ids, vec![4, 42]);
+        assert_relative_eq!(topology.interpolate([1.0, 1.0]).unwrap()[0], 4.0, epsilon = 1e-9);
+    }
+}
@@ -31,6 +31,32 @@ impl QNNLayer {
         }
     }
     
+    /// Create a layer from explicit `(output, input)` weights and biases
+    pub fn from_parameters(weights: Array2<f64>, biases: Array1<f64>, learning_rate: f64) -> Self {
+        assert_eq!(weights.nrows(), biases.len(), "One bias per output neuron");
+        QNNLayer {
+            weights,
+            biases,
+            learning_rate,
+        }
+    }
+
+    pub fn weights(&self) -> &Array2<f64> {
+        &self.weights
+    }
+
+    pub fn biases(&self) -> &Array1<f64> {
+        &self.biases
+    }
+
+    pub fn input_dim(&self) -> usize {
+        self.weights.ncols()
+    }
+
+    pub fn output_dim(&self) -> usize {
+        self.weights.nrows()
+    }
+
     /// Quantum-inspired activation function
     fn quantum_activation(&self, x: f64) -> f64 {
         x.sin() * x.exp()
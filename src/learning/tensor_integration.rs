@@ -74,6 +74,32 @@ impl QNetwork {
         }
     }
     
+    /// Assemble a network from consecutive layers
+    pub fn from_layers(layers: Vec<QNNLayer>, learning_rate: f64) -> Self {
+        assert!(!layers.is_empty(), "Need at least one layer");
+        assert!(layers.windows(2).all(|w| w[0].output_dim() == w[1].input_dim()),
+                "Consecutive layer dimensions must match");
+        QNetwork {
+            layers,
+            learning_rate,
+        }
+    }
+
+    pub fn layers(&self) -> &[QNNLayer] {
+        &self.layers
+    }
+
+    pub fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// Neuron counts from the input layer to the output layer
+    pub fn layer_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.layers.first().map(|l| l.input_dim()).into_iter().collect();
+        sizes.extend(self.layers.iter().map(|l| l.output_dim()));
+        sizes
+    }
+
     /// Get the output dimension of the network
     pub fn output_dim(&self) -> usize {
         self.layers.last().map(|l| l.weights.shape()[0]).unwrap_or(0)
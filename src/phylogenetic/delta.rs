@@ -2,7 +2,246 @@
 #![allow(dead_code)]
 
 use crate::core::tensor::MorphicTensor;
-use crate::phylogenetic::PhylogeneticRuntime;
+use crate::phylogenetic::{PhylogeneticRuntime, VersionId};
+use crate::quantum::state::QuantumState;
+
+/// One edit to a tensor
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeltaOp {
+    Set { index: usize, value: f64 },
+    Add { index: usize, amount: f64 },
+    Scale { index: usize, factor: f64 },
+    /// Sparse patch setting several data entries at once
+    Patch(Vec<(usize, f64)>),
+    MoveTo([f64; 2]),
+    Translate([f64; 2]),
+    Connect(usize),
+    Disconnect(usize),
+    SetStrength(f64),
+    Transition(QuantumState),
+}
+
+impl DeltaOp {
+    /// Whether the op edits `spatial.data`
+    fn touches_data(&self) -> bool {
+        matches!(self, DeltaOp::Set { .. } | DeltaOp::Add { .. } | DeltaOp::Scale { .. } | DeltaOp::Patch(_))
+    }
+
+    /// Data indices the op writes
+    fn indices(&self) -> Vec<usize> {
+        match self {
+            DeltaOp::Set { index, .. } | DeltaOp::Add { index, .. } | DeltaOp::Scale { index, .. } => vec![*index],
+            DeltaOp::Patch(entries) => entries.iter().map(|&(i, _)| i).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Apply the op and return the op that restores the previous state exactly
+    fn apply(&self, tensor: &mut MorphicTensor) -> Result<DeltaOp, String> {
+        if self.is_noop() {
+            return Ok(self.clone());
+        }
+        if self.touches_data() {
+            let data = tensor.spatial.data.as_mut()
+                .ok_or_else(|| "Delta edits data but the tensor has none".to_string())?;
+            if let Some(&index) = self.indices().iter().find(|&&i| i >= data.len()) {
+                return Err(format!("Delta index {} out of bounds for data of length {}", index, data.len()));
+            }
+            let undo = DeltaOp::Patch(self.indices().iter().map(|&i| (i, data[i])).collect());
+            match self {
+                DeltaOp::Set { index, value } => data[*index] = *value,
+                DeltaOp::Add { index, amount } => data[*index] += amount,
+                DeltaOp::Scale { index, factor } => data[*index] *= factor,
+                DeltaOp::Patch(entries) => {
+                    for &(i, value) in entries {
+                        data[i] = value;
+                    }
+                }
+                _ => unreachable!("only data ops reach here"),
+            }
+            // A single-entry undo reads better as a Set
+            return Ok(match undo {
+                DeltaOp::Patch(entries) if entries.len() == 1 => {
+                    DeltaOp::Set { index: entries[0].0, value: entries[0].1 }
+                }
+                other => other,
+            });
+        }
+
+        let undo = match self {
+            DeltaOp::MoveTo(position) => {
+                let previous = tensor.spatial.coordinates;
+                tensor.spatial.coordinates = *position;
+                DeltaOp::MoveTo(previous)
+            }
+            DeltaOp::Translate([dx, dy]) => {
+                let previous = tensor.spatial.coordinates;
+                tensor.spatial.coordinates = [previous[0] + dx, previous[1] + dy];
+                DeltaOp::MoveTo(previous)
+            }
+            DeltaOp::Connect(id) => {
+                let connections = &mut tensor.entanglement.connections;
+                if connections.contains(id) {
+                    return Ok(DeltaOp::Patch(Vec::new()));
+                }
+                connections.push(*id);
+                DeltaOp::Disconnect(*id)
+            }
+            DeltaOp::Disconnect(id) => {
+                let connections = &mut tensor.entanglement.connections;
+                let before = connections.len();
+                connections.retain(|c| c != id);
+                if connections.len() == before {
+                    return Ok(DeltaOp::Patch(Vec::new()));
+                }
+                DeltaOp::Connect(*id)
+            }
+            DeltaOp::SetStrength(strength) => {
+                let previous = tensor.entanglement.strength;
+                tensor.entanglement.strength = *strength;
+                DeltaOp::SetStrength(previous)
+            }
+            DeltaOp::Transition(state) => {
+                let previous = tensor.quantum_state;
+                tensor.quantum_state = *state;
+                DeltaOp::Transition(previous)
+            }
+            _ => unreachable!("data ops handled above"),
+        };
+        Ok(undo)
+    }
+
+    /// State-independent inverse, if the op has one
+    fn inverse(&self) -> Option<DeltaOp> {
+        match self {
+            DeltaOp::Add { index, amount } => Some(DeltaOp::Add { index: *index, amount: -amount }),
+            DeltaOp::Scale { index, factor } if *factor != 0.0 => {
+                Some(DeltaOp::Scale { index: *index, factor: 1.0 / factor })
+            }
+            DeltaOp::Translate([dx, dy]) => Some(DeltaOp::Translate([-dx, -dy])),
+            _ => None,
+        }
+    }
+
+    /// Fuse `self` followed by `next` into one op when they edit the same target
+    fn fuse(&self, next: &DeltaOp) -> Option<DeltaOp> {
+        use DeltaOp::*;
+        match (self, next) {
+            (Add { index: i, amount: a }, Add { index: j, amount: b }) if i == j => Some(Add { index: *i, amount: a + b }),
+            (Scale { index: i, factor: a }, Scale { index: j, factor: b }) if i == j => Some(Scale { index: *i, factor: a * b }),
+            (Set { index: i, value }, Add { index: j, amount }) if i == j => Some(Set { index: *i, value: value + amount }),
+            (Set { index: i, value }, Scale { index: j, factor }) if i == j => Some(Set { index: *i, value: value * factor }),
+            (Set { index: i, .. } | Add { index: i, .. } | Scale { index: i, .. }, Set { index: j, value }) if i == j => {
+                Some(Set { index: *j, value: *value })
+            }
+            (Translate([a, b]), Translate([c, d])) => Some(Translate([a + c, b + d])),
+            (MoveTo([x, y]), Translate([dx, dy])) => Some(MoveTo([x + dx, y + dy])),
+            (MoveTo(_) | Translate(_), MoveTo(position)) => Some(MoveTo(*position)),
+            (SetStrength(_), SetStrength(strength)) => Some(SetStrength(*strength)),
+            (Transition(_), Transition(state)) => Some(Transition(*state)),
+            _ => None,
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        matches!(self, DeltaOp::Patch(entries) if entries.is_empty())
+    }
+}
+
+/// Ordered list of edits applied to a tensor as one developmental step
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Delta {
+    pub ops: Vec<DeltaOp>,
+}
+
+impl Delta {
+    pub fn new(ops: Vec<DeltaOp>) -> Self {
+        Delta { ops }
+    }
+
+    /// Add 1.0 at each index, the original index-list delta
+    pub fn increments(indices: &[usize]) -> Self {
+        Delta::new(indices.iter().map(|&index| DeltaOp::Add { index, amount: 1.0 }).collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Apply every op in order and return the delta that undoes them
+    ///
+    /// The tensor is left unchanged if any op fails.
+    pub fn apply(&self, tensor: &mut MorphicTensor) -> Result<Delta, String> {
+        let mut edited = tensor.clone();
+        let mut undo = Vec::with_capacity(self.ops.len());
+        for op in &self.ops {
+            let inverse = op.apply(&mut edited)?;
+            if !inverse.is_noop() {
+                undo.push(inverse);
+            }
+        }
+        *tensor = edited;
+        undo.reverse();
+        Ok(Delta::new(undo))
+    }
+
+    /// State-independent inverse, or `None` if an op discards information
+    ///
+    /// Sets, patches, absolute moves, entanglement edits and transitions depend on
+    /// the prior state; use the delta returned by [`Delta::apply`] to undo those.
+    pub fn inverse(&self) -> Option<Delta> {
+        self.ops.iter().rev().map(DeltaOp::inverse).collect::<Option<Vec<_>>>().map(Delta::new)
+    }
+
+    /// `self` followed by `next`, with adjacent edits of the same target fused
+    pub fn compose(&self, next: &Delta) -> Delta {
+        let mut ops: Vec<DeltaOp> = Vec::with_capacity(self.ops.len() + next.ops.len());
+        for op in self.ops.iter().chain(&next.ops) {
+            match ops.last().and_then(|last| last.fuse(op)) {
+                Some(fused) => {
+                    ops.pop();
+                    if !fused.is_noop() {
+                        ops.push(fused);
+                    }
+                }
+                None => ops.push(op.clone()),
+            }
+        }
+        Delta::new(ops)
+    }
+
+    /// Combine two deltas made against the same base state
+    ///
+    /// Succeeds when the deltas commute: they write disjoint data indices, or
+    /// only add to shared ones, and do not both move the tensor, set its entanglement
+    /// strength or transition its state.
+    pub fn merge(&self, other: &Delta) -> Result<Delta, String> {
+        for a in &self.ops {
+            for b in &other.ops {
+                if !Self::commute(a, b) {
+                    return Err(format!("Conflicting delta edits: {:?} and {:?}", a, b));
+                }
+            }
+        }
+        Ok(self.compose(other))
+    }
+
+    fn commute(a: &DeltaOp, b: &DeltaOp) -> bool {
+        use DeltaOp::*;
+        match (a, b) {
+            (Add { .. }, Add { .. }) | (Scale { .. }, Scale { .. }) => true,
+            _ if a.touches_data() && b.touches_data() => {
+                let shared = a.indices();
+                !b.indices().iter().any(|i| shared.contains(i))
+            }
+            (Translate(_), Translate(_)) => true,
+            (MoveTo(_) | Translate(_), MoveTo(_) | Translate(_)) => false,
+            (SetStrength(_), SetStrength(_)) | (Transition(_), Transition(_)) => a == b,
+            (Connect(x) | Disconnect(x), Connect(y) | Disconnect(y)) => x != y || a == b,
+            _ => true,
+        }
+    }
+}
 
 pub struct DeltaApplicator {
     pub runtime: PhylogeneticRuntime,
@@ -13,8 +252,91 @@ impl DeltaApplicator {
         DeltaApplicator { runtime }
     }
 
-    pub fn apply_delta(&self, tensor: &mut MorphicTensor, delta: &[usize]) {
-        self.runtime.apply_mutation_in_superposition(tensor, delta);
+    /// Apply a delta and record the resulting state as a new version
+    ///
+    /// The delta only takes effect on tensors in superposition, so the version
+    /// records an empty delta otherwise.
+    pub fn apply_delta(&mut self, tensor: &mut MorphicTensor, delta: &Delta) -> Result<VersionId, String> {
+        let applied = if tensor.quantum_state == QuantumState::Superposition { delta.clone() } else { Delta::default() };
+        self.runtime.apply_mutation_in_superposition(tensor, delta)?;
         self.runtime.collapse_dead_live(tensor);
+        Ok(self.runtime.tree.commit(tensor, applied))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_apply_and_undo_restores_tensor() {
+        let mut tensor = MorphicTensor::from_data(array![1.0, 2.0, 3.0]);
+        let original = tensor.clone();
+        let delta = Delta::new(vec![
+            DeltaOp::Set { index: 0, value: 5.0 },
+            DeltaOp::Scale { index: 1, factor: 0.0 },
+            DeltaOp::Patch(vec![(2, -1.0), (0, 7.0)]),
+            DeltaOp::Translate([1.0, -2.0]),
+            DeltaOp::Connect(4),
+            DeltaOp::SetStrength(0.6),
+            DeltaOp::Transition(QuantumState::Superposition),
+        ]);
+
+        let undo = delta.apply(&mut tensor).unwrap();
+        assert_eq!(tensor.to_array(), array![7.0, 0.0, -1.0]);
+        assert_eq!(tensor.position(), [1.0, -2.0]);
+        assert_eq!(tensor.entanglement.connections, vec![4]);
+        assert!(delta.inverse().is_none());
+
+        undo.apply(&mut tensor).unwrap();
+        assert_eq!(tensor.to_array(), original.to_array());
+        assert_eq!(tensor.position(), original.position());
+        assert!(tensor.entanglement.connections.is_empty());
+        assert_eq!(tensor.quantum_state, original.quantum_state);
+
+        // Failed deltas leave the tensor untouched
+        let bad = Delta::new(vec![DeltaOp::Add { index: 0, amount: 1.0 }, DeltaOp::Set { index: 9, value: 0.0 }]);
+        assert!(bad.apply(&mut tensor).is_err());
+        assert_eq!(tensor.to_array(), original.to_array());
+    }
+
+    #[test]
+    fn test_compose_inverse_and_merge() {
+        let a = Delta::new(vec![DeltaOp::Add { index: 0, amount: 2.0 }, DeltaOp::Translate([1.0, 0.0])]);
+        let b = Delta::new(vec![DeltaOp::Translate([0.0, 1.0]), DeltaOp::Add { index: 1, amount: 1.0 }]);
+        let composed = a.compose(&b);
+        assert_eq!(composed.ops.len(), 3);
+        assert_eq!(composed.ops[1], DeltaOp::Translate([1.0, 1.0]));
+
+        let mut tensor = MorphicTensor::from_data(array![0.0, 0.0]);
+        composed.apply(&mut tensor).unwrap();
+        composed.inverse().unwrap().apply(&mut tensor).unwrap();
+        assert_eq!(tensor.to_array(), array![0.0, 0.0]);
+        assert_eq!(tensor.position(), [0.0, 0.0]);
+
+        assert!(Delta::increments(&[0]).compose(&Delta::increments(&[0])).ops
+            == vec![DeltaOp::Add { index: 0, amount: 2.0 }]);
+        assert!(a.merge(&b).is_ok());
+        let conflicting = Delta::new(vec![DeltaOp::Set { index: 0, value: 1.0 }]);
+        assert!(a.merge(&conflicting).is_err());
+    }
+
+    #[test]
+    fn test_compose_keeps_connect_then_disconnect() {
+        // The connection already exists, so the pair removes it rather than doing nothing
+        let mut tensor = MorphicTensor::from_data(array![0.0]);
+        tensor.entanglement.connections.push(4);
+        let connect = Delta::new(vec![DeltaOp::Connect(4)]);
+        let disconnect = Delta::new(vec![DeltaOp::Disconnect(4)]);
+
+        let mut stepwise = tensor.clone();
+        connect.apply(&mut stepwise).unwrap();
+        disconnect.apply(&mut stepwise).unwrap();
+        let composed = connect.compose(&disconnect);
+        assert_eq!(composed.ops.len(), 2);
+        composed.apply(&mut tensor).unwrap();
+        assert!(stepwise.entanglement.connections.is_empty());
+        assert_eq!(tensor.entanglement.connections, stepwise.entanglement.connections);
     }
 }
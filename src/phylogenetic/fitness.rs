@@ -0,0 +1,132 @@
+// Fitness functions for environmental selection
+#![allow(dead_code)]
+
+use crate::core::tensor::MorphicTensor;
+use crate::environment::Environment;
+use crate::field_stabilization::FieldStabilizer;
+use ndarray::Array1;
+
+/// Scores a tensor; larger is fitter
+pub trait Fitness {
+    fn evaluate(&self, tensor: &MorphicTensor) -> f64;
+}
+
+impl<F> Fitness for F
+where
+    F: Fn(&MorphicTensor) -> f64,
+{
+    fn evaluate(&self, tensor: &MorphicTensor) -> f64 {
+        self(tensor)
+    }
+}
+
+/// The original survival rule: entanglement strength scaled by selection pressure
+pub struct EntanglementFitness {
+    pub pressure: f64,
+}
+
+impl Fitness for EntanglementFitness {
+    fn evaluate(&self, tensor: &MorphicTensor) -> f64 {
+        tensor.entanglement.strength * self.pressure
+    }
+}
+
+/// Negative developmental energy, so lower-energy tensors are fitter
+pub struct EnergyFitness {
+    pub stabilizer: FieldStabilizer,
+}
+
+impl Fitness for EnergyFitness {
+    fn evaluate(&self, tensor: &MorphicTensor) -> f64 {
+        -self.stabilizer.developmental_energy(tensor)
+    }
+}
+
+/// Mean episode return of the tensor acting as a linear policy in an environment
+///
+/// The tensor data is read row-major as an `actions × state` weight matrix (missing
+/// entries are zero) and the policy takes the action with the largest score. Each
+/// episode runs in a fresh environment from `make_environment`.
+pub struct EnvironmentRollout<F> {
+    pub make_environment: F,
+    pub episodes: usize,
+    pub max_steps: usize,
+}
+
+impl<F, E> EnvironmentRollout<F>
+where
+    F: Fn() -> E,
+    E: Environment,
+{
+    pub fn new(make_environment: F, episodes: usize, max_steps: usize) -> Self {
+        EnvironmentRollout { make_environment, episodes, max_steps }
+    }
+
+    /// Action chosen by the tensor's linear policy in `state`
+    pub fn act(tensor: &MorphicTensor, state: &Array1<f64>, actions: usize) -> usize {
+        let weights = tensor.to_array();
+        let score = |action: usize| -> f64 {
+            state.iter()
+                .enumerate()
+                .map(|(i, s)| weights.get(action * state.len() + i).copied().unwrap_or(0.0) * s)
+                .sum()
+        };
+        // Ties (e.g. a zero state) fall back to the weight of the action's first input
+        (0..actions)
+            .max_by(|&a, &b| {
+                score(a).total_cmp(&score(b)).then_with(|| {
+                    let bias = |x: usize| weights.get(x * state.len()).copied().unwrap_or(0.0);
+                    bias(a).total_cmp(&bias(b))
+                })
+            })
+            .unwrap_or(0)
+    }
+}
+
+impl<F, E> Fitness for EnvironmentRollout<F>
+where
+    F: Fn() -> E,
+    E: Environment,
+{
+    fn evaluate(&self, tensor: &MorphicTensor) -> f64 {
+        if self.episodes == 0 {
+            return 0.0;
+        }
+        let mut total = 0.0;
+        for _ in 0..self.episodes {
+            let mut environment = (self.make_environment)();
+            let mut state = environment.reset();
+            let actions = environment.action_space_size();
+            for _ in 0..self.max_steps {
+                let action = Self::act(tensor, &state, actions);
+                let (next, reward, done) = environment.step(action);
+                total += reward;
+                state = next;
+                if done {
+                    break;
+                }
+            }
+        }
+        total / self.episodes as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::TestEnvironment;
+    use ndarray::array;
+
+    #[test]
+    fn test_rollout_rewards_better_policy() {
+        let rollout = EnvironmentRollout::new(|| TestEnvironment::new(4, 5), 2, 10);
+        // Action 0 earns the largest reward in the test environment
+        let greedy = MorphicTensor::from_data(array![1.0, 0.0, 0.0, 0.0]);
+        let mut poor = MorphicTensor::from_data(Array1::zeros(16));
+        poor.spatial.data.as_mut().unwrap()[12] = 1.0;
+        assert!(rollout.evaluate(&greedy) > rollout.evaluate(&poor));
+
+        let closure = |t: &MorphicTensor| t.norm();
+        assert_eq!(closure.evaluate(&greedy), 1.0);
+    }
+}
@@ -13,11 +13,11 @@ impl QuantumForker {
         QuantumForker { runtime }
     }
 
-    pub fn fork_tensor(&self, original: &MorphicTensor) -> MorphicTensor {
+    pub fn fork_tensor(&mut self, original: &MorphicTensor) -> MorphicTensor {
         self.runtime.quantum_fork(original)
     }
 
-    pub fn bulk_fork(&self, originals: &[MorphicTensor]) -> Vec<MorphicTensor> {
+    pub fn bulk_fork(&mut self, originals: &[MorphicTensor]) -> Vec<MorphicTensor> {
         originals.iter().map(|t| self.fork_tensor(t)).collect()
     }
 }
@@ -0,0 +1,440 @@
+// Phylogenetic version tree of tensor states
+#![allow(dead_code)]
+
+use crate::core::tensor::MorphicTensor;
+use crate::phylogenetic::delta::Delta;
+use ndarray::Array1;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+pub type VersionId = usize;
+
+/// One recorded state of a tensor
+#[derive(Debug, Clone)]
+pub struct VersionNode {
+    pub id: VersionId,
+    /// Parent versions; the first is the lineage the tensor's path follows,
+    /// further parents record merges (e.g. crossover or entanglement)
+    pub parents: Vec<VersionId>,
+    /// Delta applied to the first parent to produce this version
+    pub delta: Delta,
+    pub created: SystemTime,
+    /// Longest distance to a root
+    pub generation: usize,
+    pub fitness: Option<f64>,
+    /// Tensor state at this version
+    pub snapshot: MorphicTensor,
+}
+
+/// Lineage between two versions through their most recent common ancestor
+#[derive(Debug, Clone)]
+pub struct VersionDiff {
+    pub ancestor: VersionId,
+    /// Versions undone going from `from` back to the ancestor (ancestor excluded)
+    pub reverted: Vec<VersionId>,
+    /// Versions replayed going from the ancestor to `to` (ancestor excluded)
+    pub applied: Vec<VersionId>,
+    /// `to − from` of the tensor data, when both versions carry data of equal length
+    pub data_change: Option<Array1<f64>>,
+}
+
+static NEXT_TREE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// DAG of tensor versions
+///
+/// Tensors point into the tree through `temporal.versions`, the first-parent lineage
+/// from a root to their current version, tagged with the tree's `id`.
+#[derive(Debug, Clone)]
+pub struct PhylogeneticTree {
+    /// Process-unique identity stamped on the paths of recorded tensors
+    id: u64,
+    nodes: Vec<VersionNode>,
+    children: HashMap<VersionId, Vec<VersionId>>,
+}
+
+impl Default for PhylogeneticTree {
+    fn default() -> Self {
+        PhylogeneticTree::new()
+    }
+}
+
+impl PhylogeneticTree {
+    pub fn new() -> Self {
+        PhylogeneticTree {
+            id: NEXT_TREE_ID.fetch_add(1, Ordering::Relaxed),
+            nodes: Vec::new(),
+            children: HashMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn get(&self, id: VersionId) -> Option<&VersionNode> {
+        self.nodes.get(id)
+    }
+
+    pub fn children(&self, id: VersionId) -> &[VersionId] {
+        self.children.get(&id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Whether the tensor's current version is recorded in this tree
+    pub fn tracks(&self, tensor: &MorphicTensor) -> bool {
+        tensor.temporal.tree == Some(self.id)
+            && tensor.temporal.current().is_some_and(|id| id < self.nodes.len())
+    }
+
+    /// Record the tensor as a new root
+    pub fn root(&mut self, tensor: &mut MorphicTensor) -> VersionId {
+        tensor.temporal.versions.clear();
+        self.record(tensor, Vec::new(), Delta::default())
+    }
+
+    /// Record the tensor's current state as a child of its current version
+    ///
+    /// Untracked tensors become a new root instead.
+    pub fn commit(&mut self, tensor: &mut MorphicTensor, delta: Delta) -> VersionId {
+        if !self.tracks(tensor) {
+            let id = self.root(tensor);
+            self.nodes[id].delta = delta;
+            return id;
+        }
+        let parent = tensor.temporal.current().expect("tracked tensors have a version");
+        self.record(tensor, vec![parent], delta)
+    }
+
+    /// Record the tensor as a merge of several parent versions
+    ///
+    /// The first parent continues the tensor's lineage.
+    pub fn merge(&mut self, tensor: &mut MorphicTensor, parents: &[VersionId]) -> Result<VersionId, String> {
+        let Some(&first) = parents.first() else {
+            return Err("A merge needs at least one parent".to_string());
+        };
+        if let Some(&missing) = parents.iter().find(|&&p| p >= self.nodes.len()) {
+            return Err(format!("Unknown parent version {}", missing));
+        }
+        tensor.temporal.versions = self.path_to_root(first).into_iter().rev().collect();
+        tensor.temporal.tree = Some(self.id);
+        Ok(self.record(tensor, parents.to_vec(), Delta::default()))
+    }
+
+    fn record(&mut self, tensor: &mut MorphicTensor, parents: Vec<VersionId>, delta: Delta) -> VersionId {
+        let id = self.nodes.len();
+        let generation = parents.iter()
+            .map(|&p| self.nodes[p].generation + 1)
+            .max()
+            .unwrap_or(0);
+        for &parent in &parents {
+            self.children.entry(parent).or_default().push(id);
+        }
+        tensor.temporal.versions.push(id);
+        tensor.temporal.tree = Some(self.id);
+        self.nodes.push(VersionNode {
+            id,
+            parents,
+            delta,
+            created: SystemTime::now(),
+            generation,
+            fitness: None,
+            snapshot: tensor.clone(),
+        });
+        id
+    }
+
+    pub fn set_fitness(&mut self, id: VersionId, fitness: f64) -> bool {
+        match self.nodes.get_mut(id) {
+            Some(node) => {
+                node.fitness = Some(fitness);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Version with the highest recorded fitness
+    pub fn fittest(&self) -> Option<VersionId> {
+        self.nodes.iter()
+            .filter_map(|n| n.fitness.map(|f| (n.id, f)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// First-parent chain from `id` back to its root, `id` first
+    pub fn path_to_root(&self, id: VersionId) -> Vec<VersionId> {
+        let mut path = Vec::new();
+        let mut current = self.nodes.get(id).map(|n| n.id);
+        while let Some(version) = current {
+            path.push(version);
+            current = self.nodes[version].parents.first().copied();
+        }
+        path
+    }
+
+    /// All ancestors of `id` through any parent, including `id` itself
+    pub fn ancestors(&self, id: VersionId) -> HashSet<VersionId> {
+        let mut seen = HashSet::new();
+        if id >= self.nodes.len() {
+            return seen;
+        }
+        let mut stack = vec![id];
+        while let Some(version) = stack.pop() {
+            if seen.insert(version) {
+                stack.extend(&self.nodes[version].parents);
+            }
+        }
+        seen
+    }
+
+    /// Most recent common ancestor: the shared ancestor of highest generation
+    ///
+    /// Ties between equally deep ancestors (possible after merges) go to the one
+    /// recorded last.
+    pub fn mrca(&self, a: VersionId, b: VersionId) -> Option<VersionId> {
+        let ancestors_a = self.ancestors(a);
+        self.ancestors(b)
+            .intersection(&ancestors_a)
+            .max_by_key(|&&id| (self.nodes[id].generation, id))
+            .copied()
+    }
+
+    /// Shortest parent chain from `descendant` up to `ancestor`, both included
+    pub fn lineage_between(&self, descendant: VersionId, ancestor: VersionId) -> Option<Vec<VersionId>> {
+        if descendant >= self.nodes.len() || ancestor >= self.nodes.len() {
+            return None;
+        }
+        let mut previous: HashMap<VersionId, VersionId> = HashMap::new();
+        let mut queue = VecDeque::from([descendant]);
+        let mut seen = HashSet::from([descendant]);
+        while let Some(version) = queue.pop_front() {
+            if version == ancestor {
+                let mut path = vec![ancestor];
+                let mut step = ancestor;
+                while let Some(&next) = previous.get(&step) {
+                    path.push(next);
+                    step = next;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &parent in &self.nodes[version].parents {
+                if seen.insert(parent) {
+                    previous.insert(parent, version);
+                    queue.push_back(parent);
+                }
+            }
+        }
+        None
+    }
+
+    /// Composition of the deltas recorded from `ancestor` down to `descendant`
+    ///
+    /// Replaying the result on the ancestor's state reproduces the descendant when
+    /// every step along the lineage was recorded with its delta.
+    pub fn lineage_delta(&self, ancestor: VersionId, descendant: VersionId) -> Option<Delta> {
+        let lineage = self.lineage_between(descendant, ancestor)?;
+        Some(lineage.iter()
+            .rev()
+            .skip(1)
+            .fold(Delta::default(), |acc, &id| acc.compose(&self.nodes[id].delta)))
+    }
+
+    /// Versions separating `from` and `to`, routed through their common ancestor
+    pub fn diff(&self, from: VersionId, to: VersionId) -> Option<VersionDiff> {
+        let ancestor = self.mrca(from, to)?;
+        let mut reverted = self.lineage_between(from, ancestor)?;
+        reverted.pop();
+        let mut applied = self.lineage_between(to, ancestor)?;
+        applied.pop();
+        applied.reverse();
+
+        let data_change = match (&self.nodes[from].snapshot.spatial.data, &self.nodes[to].snapshot.spatial.data) {
+            (Some(a), Some(b)) if a.len() == b.len() => Some(b - a),
+            _ => None,
+        };
+        Some(VersionDiff { ancestor, reverted, applied, data_change })
+    }
+
+    /// Drop every version more than `depth` parent links behind the tracked tensors
+    ///
+    /// Surviving versions are renumbered in order and the tree takes a fresh identity,
+    /// so only the tensors passed in stay tracked (their paths are remapped and lose
+    /// the pruned prefix); any other tensor or stored version ID becomes stale.
+    /// Versions whose parents were all pruned become roots but keep their generation.
+    /// Returns the number of versions removed.
+    pub fn prune<'a>(&mut self, tensors: impl IntoIterator<Item = &'a mut MorphicTensor>, depth: usize) -> usize {
+        let live: Vec<&mut MorphicTensor> = tensors.into_iter().filter(|t| self.tracks(t)).collect();
+        let mut distance: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut queue = VecDeque::new();
+        for tensor in &live {
+            let current = tensor.temporal.current().expect("tracked tensors have a version");
+            if distance[current].is_none() {
+                distance[current] = Some(0);
+                queue.push_back(current);
+            }
+        }
+        while let Some(version) = queue.pop_front() {
+            let d = distance[version].expect("queued versions have a distance");
+            if d == depth {
+                continue;
+            }
+            for &parent in &self.nodes[version].parents {
+                if distance[parent].is_none() {
+                    distance[parent] = Some(d + 1);
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        let mut remap: Vec<Option<VersionId>> = vec![None; self.nodes.len()];
+        let mut kept = 0;
+        for (old, d) in distance.iter().enumerate() {
+            if d.is_some() {
+                remap[old] = Some(kept);
+                kept += 1;
+            }
+        }
+        let removed = self.nodes.len() - kept;
+        let nodes = std::mem::take(&mut self.nodes);
+        self.children.clear();
+        for (old, mut node) in nodes.into_iter().enumerate() {
+            let Some(id) = remap[old] else { continue };
+            node.id = id;
+            node.parents = node.parents.iter().filter_map(|&p| remap[p]).collect();
+            for &parent in &node.parents {
+                self.children.entry(parent).or_default().push(id);
+            }
+            self.nodes.push(node);
+        }
+
+        self.id = NEXT_TREE_ID.fetch_add(1, Ordering::Relaxed);
+        for tensor in live {
+            // Older entries can survive through a merge, but the path stops at the first gap
+            let mut path: Vec<VersionId> = tensor.temporal.versions.iter().rev().map_while(|&v| remap[v]).collect();
+            path.reverse();
+            tensor.temporal.versions = path;
+            tensor.temporal.tree = Some(self.id);
+        }
+        removed
+    }
+
+    /// Restore a tensor to an ancestor of its current version
+    pub fn rollback(&self, tensor: &mut MorphicTensor, ancestor: VersionId) -> Result<(), String> {
+        let current = tensor.temporal.current()
+            .filter(|_| self.tracks(tensor))
+            .ok_or_else(|| "Tensor is not tracked by this phylogenetic tree".to_string())?;
+        if !self.ancestors(current).contains(&ancestor) {
+            return Err(format!("Version {} is not an ancestor of version {}", ancestor, current));
+        }
+        *tensor = self.nodes[ancestor].snapshot.clone();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_lineage_queries() {
+        let mut tree = PhylogeneticTree::new();
+        let mut base = MorphicTensor::from_data(array![0.0, 0.0]);
+        let root = tree.root(&mut base);
+
+        let step = |tensor: &mut MorphicTensor, tree: &mut PhylogeneticTree, index: usize| {
+            let delta = Delta::increments(&[index]);
+            delta.apply(tensor).unwrap();
+            tree.commit(tensor, delta)
+        };
+        let mut left = base.clone();
+        let l1 = step(&mut left, &mut tree, 0);
+        let l2 = step(&mut left, &mut tree, 0);
+
+        let mut right = base.clone();
+        let r1 = step(&mut right, &mut tree, 1);
+
+        assert_eq!(left.temporal.versions, vec![root, l1, l2]);
+        assert_eq!(tree.path_to_root(l2), vec![l2, l1, root]);
+        assert_eq!(tree.mrca(l2, r1), Some(root));
+        assert_eq!(tree.mrca(l2, l1), Some(l1));
+
+        let diff = tree.diff(l2, r1).unwrap();
+        assert_eq!(diff.reverted, vec![l2, l1]);
+        assert_eq!(diff.applied, vec![r1]);
+        assert_eq!(diff.data_change.unwrap(), array![-2.0, 1.0]);
+
+        let mut replayed = base.clone();
+        tree.lineage_delta(root, l2).unwrap().apply(&mut replayed).unwrap();
+        assert_eq!(replayed.to_array(), left.to_array());
+
+        let mut child = left.clone();
+        let merged = tree.merge(&mut child, &[l2, r1]).unwrap();
+        assert_eq!(tree.get(merged).unwrap().generation, 3);
+        assert_eq!(tree.mrca(merged, r1), Some(r1));
+
+        assert!(tree.rollback(&mut left, r1).is_err());
+        tree.rollback(&mut left, l1).unwrap();
+        assert_eq!(left.to_array(), array![1.0, 0.0]);
+        assert_eq!(left.temporal.versions, vec![root, l1]);
+    }
+
+    #[test]
+    fn test_tracks_checks_tree_identity() {
+        let mut first = PhylogeneticTree::new();
+        let mut second = PhylogeneticTree::new();
+        let mut tensor = MorphicTensor::from_data(array![1.0]);
+        let mut other = MorphicTensor::from_data(array![2.0]);
+        first.root(&mut tensor);
+        second.root(&mut other);
+        second.commit(&mut other, Delta::default());
+
+        // Version 0 exists in both trees, but only `first` recorded this tensor
+        assert!(first.tracks(&tensor));
+        assert!(!second.tracks(&tensor));
+        assert!(second.rollback(&mut tensor, 0).is_err());
+
+        let id = second.commit(&mut tensor, Delta::default());
+        assert_eq!(tensor.temporal.versions, vec![id]);
+        assert!(second.tracks(&tensor) && !first.tracks(&tensor));
+    }
+
+    #[test]
+    fn test_prune_keeps_recent_lineage() {
+        let mut tree = PhylogeneticTree::new();
+        let mut tensor = MorphicTensor::from_data(array![0.0]);
+        tree.root(&mut tensor);
+        for _ in 0..10 {
+            let delta = Delta::increments(&[0]);
+            delta.apply(&mut tensor).unwrap();
+            tree.commit(&mut tensor, delta);
+        }
+        let mut stranger = tensor.clone();
+        stranger.temporal.tree = None;
+        let mut bystander = tensor.clone();
+
+        assert_eq!(tree.prune([&mut tensor], 3), 7);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tensor.temporal.versions, vec![0, 1, 2, 3]);
+        assert!(tree.tracks(&tensor) && !tree.tracks(&bystander) && !tree.tracks(&stranger));
+        assert!(tree.get(0).unwrap().parents.is_empty());
+        assert_eq!(tree.get(3).unwrap().generation, 10);
+
+        // The surviving deltas still replay the remaining lineage
+        let mut replayed = tree.get(0).unwrap().snapshot.clone();
+        tree.lineage_delta(0, 3).unwrap().apply(&mut replayed).unwrap();
+        assert_eq!(replayed.to_array(), tensor.to_array());
+        tree.rollback(&mut tensor, 1).unwrap();
+        assert_eq!(tensor.to_array(), array![8.0]);
+        assert!(tree.rollback(&mut bystander, 1).is_err());
+    }
+}
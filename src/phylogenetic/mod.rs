@@ -4,10 +4,19 @@
 use crate::core::tensor::MorphicTensor;
 use crate::quantum::qasm::QuantumOperation;
 use crate::quantum::state::{QuantumState, QuantumSystem};
+use crate::quantum_simulation::QuantumSimulator;
+use superposition::World;
+
+/// Branch weights below this are treated as zero
+const BRANCH_THRESHOLD: f64 = 1e-12;
 
 pub struct PhylogeneticRuntime {
     pub quantum_system: QuantumSystem,
+    /// Fraction of each population culled by selection, in [0, 1]
     pub selection_pressure: f64,
+    pub tree: PhylogeneticTree,
+    /// Noise-free register used to execute scripts in superposition
+    pub simulator: QuantumSimulator,
 }
 
 impl PhylogeneticRuntime {
@@ -15,26 +24,94 @@ impl PhylogeneticRuntime {
         PhylogeneticRuntime {
             quantum_system: QuantumSystem::new(),
             selection_pressure: pressure,
+            tree: PhylogeneticTree::new(),
+            simulator: QuantumSimulator::ideal(2),
         }
     }
 
     /// Execute scripts in quantum superposition
-    pub fn execute_in_superposition(&self, tensors: &mut [MorphicTensor], _script: &[QuantumOperation]) {
+    ///
+    /// Each tensor seeds a register through the simulator and the script is applied
+    /// transversally (single-qubit gates on every qubit, CX along the qubit chain).
+    /// Every `Measure`, and the end of the script, splits each world into one branch
+    /// per basis state with non-zero amplitude. A branch is a copy of its tensor in
+    /// superposition, with the final outcome's set bits applied as increments of the
+    /// matching data entries and recorded as a child version of the tensor.
+    /// Untracked tensors are recorded as roots first.
+    pub fn execute_in_superposition(&mut self, tensors: &mut [MorphicTensor], script: &[QuantumOperation]) -> BranchSet {
         println!("Executing script in superposition on {} tensors", tensors.len());
-        // Actual quantum execution would go here
+        let mut set = BranchSet::default();
+
+        for (source, tensor) in tensors.iter_mut().enumerate() {
+            if !self.tree.tracks(tensor) {
+                self.tree.root(tensor);
+            }
+
+            let mut worlds = vec![World { outcomes: Vec::new(), state: self.simulator.initialize_state(tensor) }];
+            for operation in script {
+                match operation {
+                    QuantumOperation::Measure => {
+                        worlds = worlds.into_iter().flat_map(|w| w.split(BRANCH_THRESHOLD)).collect();
+                    }
+                    gate => {
+                        for world in &mut worlds {
+                            self.simulator.apply_transversal(&mut world.state, gate);
+                        }
+                    }
+                }
+            }
+            if !matches!(script.last(), Some(QuantumOperation::Measure)) {
+                worlds = worlds.into_iter().flat_map(|w| w.split(BRANCH_THRESHOLD)).collect();
+            }
+
+            let total: f64 = worlds.iter().map(|w| w.amplitude().norm_sqr()).sum();
+            for world in worlds {
+                let outcome = world.outcomes.last().copied().unwrap_or(0);
+                let length = tensor.spatial.data.as_ref().map_or(0, |d| d.len());
+                let mut ops: Vec<DeltaOp> = (0..self.simulator.qubit_count.min(length))
+                    .filter(|bit| outcome & (1 << bit) != 0)
+                    .map(|index| DeltaOp::Add { index, amount: 1.0 })
+                    .collect();
+                ops.push(DeltaOp::Transition(QuantumState::Superposition));
+                let delta = Delta::new(ops);
+
+                // An exact copy, so the recorded delta alone explains the branch
+                let mut branch = tensor.clone();
+                delta.apply(&mut branch).expect("outcome delta stays within the tensor data");
+                let version = self.tree.commit(&mut branch, delta);
+
+                let amplitude = world.amplitude();
+                set.branches.push(Branch {
+                    source,
+                    outcomes: world.outcomes,
+                    amplitude,
+                    probability: if total > 0.0 { amplitude.norm_sqr() / total } else { 0.0 },
+                    version,
+                    tensor: branch,
+                });
+            }
+        }
+
+        println!("Script produced {} branches", set.len());
+        set
     }
 
     /// Apply environmental selection pressure
+    ///
+    /// A tensor survives when its entanglement strength exceeds the pressure, so over
+    /// strengths spread across [0, 1] the pressure is the fraction culled.
     pub fn apply_selection_pressure(&self, tensor: &MorphicTensor) -> bool {
-        // Simple selection: higher entanglement survives better
-        let survival_prob = tensor.entanglement.strength * self.selection_pressure;
-        survival_prob > 0.5
+        tensor.entanglement.strength > self.selection_pressure
     }
 
     /// Apply mutation in superposition
-    pub fn apply_mutation_in_superposition(&self, tensor: &mut MorphicTensor, delta: &[usize]) {
+    ///
+    /// Returns the undo delta, or `None` when the tensor is not in superposition.
+    pub fn apply_mutation_in_superposition(&self, tensor: &mut MorphicTensor, delta: &Delta) -> Result<Option<Delta>, String> {
         if tensor.quantum_state == QuantumState::Superposition {
-            tensor.apply_phylogenetic_delta(delta);
+            delta.apply(tensor).map(Some)
+        } else {
+            Ok(None)
         }
     }
 
@@ -46,19 +123,101 @@ impl PhylogeneticRuntime {
     }
 
     /// Quantum forking implementation
-    pub fn quantum_fork(&self, original: &MorphicTensor) -> MorphicTensor {
+    ///
+    /// The fork is recorded as a child of the original's current version; an
+    /// untracked original is first recorded as a root.
+    pub fn quantum_fork(&mut self, original: &MorphicTensor) -> MorphicTensor {
         let mut fork = original.quantum_fork();
-        fork.temporal.versions.push(fork.temporal.versions.len() + 1);
+        if !self.tree.tracks(original) {
+            let mut root = original.clone();
+            self.tree.root(&mut root);
+            fork.temporal = root.temporal;
+        }
+        self.tree.commit(&mut fork, Delta::default());
         fork
     }
+
+    /// Restore a tensor to one of its recorded ancestors
+    pub fn rollback(&self, tensor: &mut MorphicTensor, version: VersionId) -> Result<(), String> {
+        self.tree.rollback(tensor, version)
+    }
 }
 
 // Sub-modules
 pub mod delta;
 pub mod selection;
 pub mod forking;
+pub mod lineage;
+pub mod fitness;
+pub mod superposition;
+pub mod population;
 
 // Re-exports
-pub use delta::DeltaApplicator;
-pub use selection::EnvironmentalSelector;
+pub use delta::{Delta, DeltaApplicator, DeltaOp};
+pub use selection::{EnvironmentalSelector, ParetoSurvivor, SelectionStrategy, Survivor};
+pub use fitness::{EnergyFitness, EntanglementFitness, EnvironmentRollout, Fitness};
 pub use forking::QuantumForker;
+pub use lineage::{PhylogeneticTree, VersionDiff, VersionId, VersionNode};
+pub use population::{CrossoverOperator, GenerationStats, MutationSchedule, PopulationManager};
+pub use superposition::{Branch, BranchSet};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    #[test]
+    fn test_superposed_execution_branches_by_amplitude() {
+        let mut runtime = PhylogeneticRuntime::new(1.0);
+        let mut tensors = vec![MorphicTensor::from_data(array![0.0, 0.0, 0.0]), MorphicTensor::void()];
+
+        // X then H on both qubits: four equally weighted worlds per tensor
+        let set = runtime.execute_in_superposition(&mut tensors, &[QuantumOperation::X, QuantumOperation::H]);
+        assert_eq!(set.len(), 8);
+        for branch in set.of_source(0) {
+            assert_relative_eq!(branch.probability, 0.25, epsilon = 1e-12);
+            assert_eq!(runtime.tree.mrca(branch.version, tensors[0].temporal.current().unwrap()),
+                       tensors[0].temporal.current());
+            let bits = branch.outcome();
+            let expected = array![(bits & 1) as f64, ((bits >> 1) & 1) as f64, 0.0];
+            assert_eq!(branch.tensor.to_array() - tensors[0].to_array(), expected);
+
+            // Replaying the recorded delta on the parent reproduces the branch exactly
+            let mut replayed = tensors[0].clone();
+            runtime.tree.get(branch.version).unwrap().delta.apply(&mut replayed).unwrap();
+            assert_eq!(replayed.to_array(), branch.tensor.to_array());
+        }
+        // H·X|0⟩ = |−⟩, so worlds with odd parity carry negative amplitude
+        let negative = set.of_source(1).filter(|b| b.amplitude.re < 0.0).count();
+        assert_eq!(negative, 2);
+
+        // A mid-script measurement splits before the second layer
+        let set = runtime.execute_in_superposition(&mut tensors[..1],
+            &[QuantumOperation::H, QuantumOperation::Measure, QuantumOperation::H]);
+        assert_eq!(set.len(), 16);
+        assert!(set.branches.iter().all(|b| b.outcomes.len() == 2));
+
+        let survivors = set.collapse(|t| t.to_array().sum());
+        assert_eq!(survivors.len(), 1);
+        assert_eq!(survivors[0].quantum_state, QuantumState::Collapsed);
+    }
+
+    #[test]
+    fn test_selection_pressure_culls_more_as_it_rises() {
+        let tensors: Vec<MorphicTensor> = (0..10).map(|i| {
+            let mut t = MorphicTensor::void();
+            t.entanglement.strength = 0.05 + i as f64 / 10.0;
+            t
+        }).collect();
+
+        let mut previous = usize::MAX;
+        for pressure in [0.0, 0.2, 0.5, 0.8] {
+            let selector = EnvironmentalSelector::new(PhylogeneticRuntime::new(pressure));
+            let surviving = tensors.iter().filter(|t| selector.runtime.apply_selection_pressure(t)).count();
+            assert_eq!(surviving, selector.survivor_count(tensors.len()));
+            assert!(surviving < previous);
+            previous = surviving;
+        }
+    }
+}
@@ -0,0 +1,365 @@
+// Generational genetic algorithm over tensor populations
+#![allow(dead_code)]
+
+use crate::core::tensor::MorphicTensor;
+use crate::evolutionary::gaussian;
+use crate::phylogenetic::delta::{Delta, DeltaOp};
+use crate::phylogenetic::fitness::Fitness;
+use crate::phylogenetic::selection::{EnvironmentalSelector, Survivor};
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+/// How the mutation step size evolves over generations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MutationSchedule {
+    Constant(f64),
+    /// Linear interpolation from `start` to `end` over `horizon` generations
+    Linear { start: f64, end: f64, horizon: usize },
+    /// `initial · decay^generation`
+    Exponential { initial: f64, decay: f64 },
+    /// Rechenberg-style: grow by `factor` after an improvement of the best fitness,
+    /// otherwise shrink by `factor^(1/4)`
+    Adaptive { initial: f64, factor: f64 },
+}
+
+impl MutationSchedule {
+    pub fn initial(&self) -> f64 {
+        match *self {
+            MutationSchedule::Constant(scale) => scale,
+            MutationSchedule::Linear { start, .. } => start,
+            MutationSchedule::Exponential { initial, .. } => initial,
+            MutationSchedule::Adaptive { initial, .. } => initial,
+        }
+    }
+
+    /// Step size for the next generation given the current one
+    pub fn next(&self, generation: usize, current: f64, improved: bool) -> f64 {
+        match *self {
+            MutationSchedule::Constant(scale) => scale,
+            MutationSchedule::Linear { start, end, horizon } => {
+                let t = if horizon == 0 { 1.0 } else { (generation as f64 / horizon as f64).min(1.0) };
+                start + (end - start) * t
+            }
+            MutationSchedule::Exponential { initial, decay } => initial * decay.powi(generation as i32),
+            MutationSchedule::Adaptive { factor, .. } => {
+                if improved { current * factor } else { current / factor.powf(0.25) }
+            }
+        }
+    }
+}
+
+/// Recombination of two parents' data vectors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrossoverOperator {
+    /// Each gene from either parent with equal probability
+    Uniform,
+    /// Prefix from the first parent, suffix from the second
+    SinglePoint,
+    /// Convex combination `α·a + (1−α)·b`
+    Arithmetic { alpha: f64 },
+    /// BLX-α: each gene uniform in the parents' interval widened by `alpha` on both sides
+    Blend { alpha: f64 },
+}
+
+impl CrossoverOperator {
+    /// Child data, or `None` when the parents' dimensions differ
+    pub fn cross<R: Rng>(&self, a: &Array1<f64>, b: &Array1<f64>, rng: &mut R) -> Option<Array1<f64>> {
+        if a.len() != b.len() {
+            return None;
+        }
+        let child = match *self {
+            CrossoverOperator::Uniform => {
+                Array1::from_shape_fn(a.len(), |i| if rng.gen::<bool>() { a[i] } else { b[i] })
+            }
+            CrossoverOperator::SinglePoint => {
+                let point = rng.gen_range(0..=a.len());
+                Array1::from_shape_fn(a.len(), |i| if i < point { a[i] } else { b[i] })
+            }
+            CrossoverOperator::Arithmetic { alpha } => a * alpha + b * (1.0 - alpha),
+            CrossoverOperator::Blend { alpha } => Array1::from_shape_fn(a.len(), |i| {
+                let (lo, hi) = (a[i].min(b[i]), a[i].max(b[i]));
+                let spread = (hi - lo) * alpha;
+                if hi - lo + 2.0 * spread > 0.0 {
+                    rng.gen_range((lo - spread)..=(hi + spread))
+                } else {
+                    lo
+                }
+            }),
+        };
+        Some(child)
+    }
+}
+
+/// Fitness summary of one evaluated generation
+#[derive(Debug, Clone)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best: f64,
+    pub mean: f64,
+    pub worst: f64,
+    pub std_dev: f64,
+    /// Mean distance of the data vectors from their centroid
+    pub diversity: f64,
+    pub mutation_scale: f64,
+    pub survivors: usize,
+}
+
+/// Runs fork → mutate → crossover → evaluate → select generations
+///
+/// The selector's runtime is the population's runtime: every child is recorded in its
+/// phylogenetic tree as a fork of its first parent, a mutation delta, and a merge with
+/// its second parent when crossover happens. When `lineage_depth` is set, the tree is
+/// pruned after each generation to that many versions behind the population and the
+/// best tensor, which renumbers versions and invalidates IDs held elsewhere.
+pub struct PopulationManager {
+    pub selector: EnvironmentalSelector,
+    pub population: Vec<MorphicTensor>,
+    pub population_size: usize,
+    pub mutation: MutationSchedule,
+    /// Probability that each gene is mutated
+    pub mutation_probability: f64,
+    pub mutation_scale: f64,
+    pub crossover: CrossoverOperator,
+    pub crossover_rate: f64,
+    pub history: Vec<GenerationStats>,
+    /// Fittest tensor evaluated so far
+    pub best: Option<(MorphicTensor, f64)>,
+    /// Parent links of ancestry kept in the tree; `None` (the default) keeps every version
+    pub lineage_depth: Option<usize>,
+    rng: StdRng,
+}
+
+impl PopulationManager {
+    /// Seed a population of `size` forks of `seeds`, cycling through them
+    pub fn new(mut selector: EnvironmentalSelector, seeds: &[MorphicTensor], size: usize) -> Self {
+        let tree = &mut selector.runtime.tree;
+        let mut roots: Vec<MorphicTensor> = seeds.to_vec();
+        for root in &mut roots {
+            if !tree.tracks(root) {
+                tree.root(root);
+            }
+        }
+        let population = (0..if roots.is_empty() { 0 } else { size })
+            .map(|i| {
+                let mut member = roots[i % roots.len()].clone();
+                tree.commit(&mut member, Delta::default());
+                member
+            })
+            .collect();
+
+        let mutation = MutationSchedule::Constant(0.1);
+        PopulationManager {
+            selector,
+            population,
+            population_size: size,
+            mutation,
+            mutation_probability: 0.2,
+            mutation_scale: mutation.initial(),
+            crossover: CrossoverOperator::Uniform,
+            crossover_rate: 0.7,
+            history: Vec::new(),
+            best: None,
+            lineage_depth: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Make selection, mutation and crossover reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn set_mutation(&mut self, schedule: MutationSchedule) {
+        self.mutation = schedule;
+        self.mutation_scale = schedule.initial();
+    }
+
+    pub fn generation(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Evaluate every member in parallel
+    pub fn evaluate(&self, fitness: &(dyn Fitness + Sync)) -> Vec<f64> {
+        self.population.par_iter().map(|tensor| fitness.evaluate(tensor)).collect()
+    }
+
+    /// Run one generation and return its statistics
+    pub fn step(&mut self, fitness: &(dyn Fitness + Sync)) -> GenerationStats {
+        let scores = self.evaluate(fitness);
+        let previous_best = self.best.as_ref().map(|(_, f)| *f);
+        if let Some((index, &score)) = scores.iter().enumerate()
+            .filter(|(_, s)| s.is_finite())
+            .max_by(|a, b| a.1.total_cmp(b.1))
+        {
+            if previous_best.is_none_or(|best| score > best) {
+                self.best = Some((self.population[index].clone(), score));
+            }
+        }
+        let improved = self.best.as_ref().map(|(_, f)| *f) != previous_best;
+
+        let survivors = self.selector.select_scored_with(&self.population, &scores, &mut self.rng);
+        let stats = self.statistics(&scores, survivors.len());
+        println!("Generation {}: best {:.4}, mean {:.4}, diversity {:.4}",
+                 stats.generation, stats.best, stats.mean, stats.diversity);
+
+        self.population = self.breed(&survivors);
+        if let Some(depth) = self.lineage_depth {
+            let best = self.best.as_mut().map(|(tensor, _)| tensor);
+            self.selector.runtime.tree.prune(self.population.iter_mut().chain(best), depth);
+        }
+        self.history.push(stats.clone());
+        self.mutation_scale = self.mutation.next(self.history.len(), self.mutation_scale, improved);
+        stats
+    }
+
+    /// Run `generations` generations
+    pub fn run(&mut self, generations: usize, fitness: &(dyn Fitness + Sync)) -> &[GenerationStats] {
+        let start = self.history.len();
+        for _ in 0..generations {
+            self.step(fitness);
+        }
+        &self.history[start..]
+    }
+
+    fn breed(&mut self, survivors: &[Survivor]) -> Vec<MorphicTensor> {
+        if survivors.is_empty() {
+            return Vec::new();
+        }
+        let rng = &mut self.rng;
+        let parents: Vec<MorphicTensor> = survivors.iter().map(|s| self.population[s.index].clone()).collect();
+        let parent_scores: Vec<f64> = survivors.iter().map(|s| s.fitness).collect();
+        let all: Vec<usize> = (0..parents.len()).collect();
+        let strategy = self.selector.strategy;
+
+        // Elites carry over unchanged
+        let elites = self.selector.elitism.min(parents.len()).min(self.population_size);
+        let mut next: Vec<MorphicTensor> = parents[..elites].to_vec();
+
+        while next.len() < self.population_size {
+            let first = strategy.pick(&parent_scores, &all, rng).expect("parents are not empty");
+            let second = strategy.pick(&parent_scores, &all, rng).expect("parents are not empty");
+            let tree = &mut self.selector.runtime.tree;
+
+            // Fork
+            let mut child = parents[first].clone();
+            tree.commit(&mut child, Delta::default());
+
+            // Mutate
+            let length = child.spatial.data.as_ref().map_or(0, |d| d.len());
+            let mut ops = Vec::new();
+            for index in 0..length {
+                if rng.gen::<f64>() < self.mutation_probability {
+                    ops.push(DeltaOp::Add { index, amount: gaussian(rng) * self.mutation_scale });
+                }
+            }
+            if !ops.is_empty() {
+                let delta = Delta::new(ops);
+                delta.apply(&mut child).expect("mutations stay within the data");
+                tree.commit(&mut child, delta);
+            }
+
+            // Crossover
+            if first != second && rng.gen::<f64>() < self.crossover_rate {
+                let crossed = match (&child.spatial.data, &parents[second].spatial.data) {
+                    (Some(a), Some(b)) => self.crossover.cross(a, b, rng),
+                    _ => None,
+                };
+                if let (Some(data), Some(own), Some(other)) =
+                    (crossed, child.temporal.current(), parents[second].temporal.current())
+                {
+                    child.spatial.data = Some(data);
+                    tree.merge(&mut child, &[own, other]).expect("parents are recorded");
+                }
+            }
+            next.push(child);
+        }
+        next
+    }
+
+    fn statistics(&self, scores: &[f64], survivors: usize) -> GenerationStats {
+        let finite: Vec<f64> = scores.iter().cloned().filter(|s| s.is_finite()).collect();
+        let n = finite.len().max(1) as f64;
+        let mean = finite.iter().sum::<f64>() / n;
+        let variance = finite.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+
+        let data: Vec<Array1<f64>> = self.population.iter()
+            .filter_map(|t| t.spatial.data.clone())
+            .collect();
+        let diversity = match data.first() {
+            Some(first) if data.iter().all(|d| d.len() == first.len()) => {
+                let centroid = data.iter().fold(Array1::<f64>::zeros(first.len()), |acc, d| acc + d) / data.len() as f64;
+                data.iter().map(|d| (d - &centroid).mapv(|x| x * x).sum().sqrt()).sum::<f64>() / data.len() as f64
+            }
+            _ => 0.0,
+        };
+
+        GenerationStats {
+            generation: self.history.len(),
+            best: finite.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            worst: finite.iter().cloned().fold(f64::INFINITY, f64::min),
+            std_dev: variance.sqrt(),
+            diversity,
+            mutation_scale: self.mutation_scale,
+            survivors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phylogenetic::selection::SelectionStrategy;
+    use crate::phylogenetic::PhylogeneticRuntime;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_ga_improves_sphere_fitness() {
+        let mut selector = EnvironmentalSelector::new(PhylogeneticRuntime::new(0.5));
+        selector.strategy = SelectionStrategy::Tournament { size: 3 };
+        selector.elitism = 2;
+        let seed = MorphicTensor::from_data(Array1::from_elem(4, 3.0));
+        let mut manager = PopulationManager::new(selector, &[seed], 30).with_seed(11);
+        manager.lineage_depth = Some(24);
+        manager.set_mutation(MutationSchedule::Exponential { initial: 0.5, decay: 0.98 });
+        manager.crossover = CrossoverOperator::Blend { alpha: 0.3 };
+
+        let target = Array1::from_vec(vec![1.0, -1.0, 0.5, 0.0]);
+        let fitness = move |t: &MorphicTensor| -(&t.to_array() - &target).mapv(|x| x * x).sum();
+        let history = manager.run(40, &fitness).to_vec();
+
+        assert_eq!(history.len(), 40);
+        assert!(history.last().unwrap().best > history[0].best);
+        assert!(manager.best.as_ref().unwrap().1 > -0.5);
+        // Elitism keeps the best fitness from regressing
+        assert!(history.windows(2).all(|w| w[1].best >= w[0].best - 1e-12));
+        assert_eq!(manager.population.len(), 30);
+        // Pruning keeps the tree to the population's recent ancestry
+        let tree = &manager.selector.runtime.tree;
+        assert!(tree.len() <= 31 * 25);
+        assert!(manager.population.iter().all(|t| tree.tracks(t)));
+        assert!(tree.tracks(&manager.best.as_ref().unwrap().0));
+    }
+
+    #[test]
+    fn test_crossover_operators() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let a = Array1::from_vec(vec![0.0; 6]);
+        let b = Array1::from_vec(vec![1.0; 6]);
+        let arithmetic = CrossoverOperator::Arithmetic { alpha: 0.25 }.cross(&a, &b, &mut rng).unwrap();
+        assert!(arithmetic.iter().all(|&x| (x - 0.75).abs() < 1e-12));
+        let point = CrossoverOperator::SinglePoint.cross(&a, &b, &mut rng).unwrap();
+        assert!(point.windows(2).into_iter().all(|w| w[0] <= w[1]));
+        let blend = CrossoverOperator::Blend { alpha: 0.5 }.cross(&a, &b, &mut rng).unwrap();
+        assert!(blend.iter().all(|&x| (-0.5..=1.5).contains(&x)));
+        assert!(CrossoverOperator::Uniform.cross(&a, &Array1::zeros(2), &mut rng).is_none());
+
+        let schedule = MutationSchedule::Adaptive { initial: 1.0, factor: 1.5 };
+        assert!(schedule.next(1, 1.0, true) > 1.0);
+        assert!(schedule.next(1, 1.0, false) < 1.0);
+    }
+}
@@ -1,5 +1,8 @@
 // Phylogenetic Runtime Test
-use morph::phylogenetic::{PhylogeneticRuntime, DeltaApplicator, EnvironmentalSelector, QuantumForker};
+use morph::phylogenetic::{PhylogeneticRuntime, Delta, DeltaApplicator, DeltaOp, EnvironmentalSelector, EntanglementFitness, EnvironmentRollout, QuantumForker, SelectionStrategy, PopulationManager, MutationSchedule, CrossoverOperator};
+use morph::environment::TestEnvironment;
+use morph::quantum::qasm::QuantumOperation;
+use morph::quantum::state::QuantumState;
 use morph::core::tensor::MorphicTensor;
 
 fn main() {
@@ -13,24 +16,92 @@ fn main() {
     tensor.entanglement.strength = 0.7;
 
     // Test delta application
-    let delta_app = DeltaApplicator::new(runtime);
-    delta_app.apply_delta(&mut tensor, &[42]);
-    println!("Applied delta: {:?}", tensor.temporal.versions);
+    let mut delta_app = DeltaApplicator::new(runtime);
+    tensor.update_from_slice(&[0.5, 1.5]);
+    tensor.quantum_state = QuantumState::Superposition;
+    let delta = Delta::new(vec![
+        DeltaOp::Add { index: 1, amount: 1.0 },
+        DeltaOp::Connect(42),
+    ]);
+    match delta_app.apply_delta(&mut tensor, &delta) {
+        Ok(version) => println!("Applied delta as version {}: {:?}", version, tensor.temporal.versions),
+        Err(e) => println!("Delta rejected: {}", e),
+    }
+    let merged = delta.merge(&Delta::increments(&[0])).expect("edits commute");
+    println!("Merged delta: {:?}", merged.ops);
 
     // Test environmental selection
-    let selector = EnvironmentalSelector::new(PhylogeneticRuntime::new(0.6));
+    let mut selector = EnvironmentalSelector::new(PhylogeneticRuntime::new(0.6));
     let tensors = vec![
         MorphicTensor::void(),
         MorphicTensor::void(),
         tensor.clone()
     ];
-    let selected = selector.select(&tensors);
-    println!("Selected indices: {:?}", selected);
+    let selected = selector.select(&tensors, &EntanglementFitness { pressure: 0.6 });
+    println!("Selected survivors: {:?}", selected);
+
+    let rollout = EnvironmentRollout::new(|| TestEnvironment::new(4, 20), 3, 20);
+    let policies: Vec<MorphicTensor> = (0..6)
+        .map(|k| MorphicTensor::from_data(ndarray::Array1::from_shape_fn(16, |i| if i == 4 * (k % 4) { 1.0 } else { 0.0 })))
+        .collect();
+    selector.strategy = SelectionStrategy::Rank;
+    println!("Rollout survivors: {:?}", selector.select(&policies, &rollout));
+
+    let energy = |t: &MorphicTensor| -t.norm();
+    let spread = |t: &MorphicTensor| t.to_array().iter().cloned().fold(0.0, f64::max);
+    let pareto = selector.select_pareto(&policies, &[&energy, &spread, &rollout]);
+    println!("Pareto survivors: {:?}", pareto.iter().map(|p| (p.index, p.front)).collect::<Vec<_>>());
 
     // Test quantum forking
-    let forker = QuantumForker::new(PhylogeneticRuntime::new(0.7));
-    let fork = forker.fork_tensor(&tensor);
+    let mut forker = QuantumForker::new(PhylogeneticRuntime::new(0.7));
+    let mut parent = MorphicTensor::from_data(ndarray::array![1.0, 2.0, 3.0]);
+    forker.runtime.tree.root(&mut parent);
+    let fork = forker.fork_tensor(&parent);
     println!("Fork created with path: {:?}", fork.temporal.versions);
 
+    // Test lineage queries
+    let siblings = forker.bulk_fork(&[fork.clone(), fork.clone()]);
+    let mut grandchild = forker.fork_tensor(&siblings[0]);
+    let (a, b) = (grandchild.temporal.current().unwrap(), siblings[1].temporal.current().unwrap());
+    let tree = &mut forker.runtime.tree;
+    tree.set_fitness(a, 0.9);
+    tree.set_fitness(b, 0.4);
+    println!("Versions recorded: {}, fittest: {:?}", tree.len(), tree.fittest());
+    println!("Path to root from {}: {:?}", a, tree.path_to_root(a));
+    println!("MRCA of {} and {}: {:?}", a, b, tree.mrca(a, b));
+    if let Some(diff) = tree.diff(a, b) {
+        println!("Diff {} -> {}: revert {:?}, apply {:?}", a, b, diff.reverted, diff.applied);
+    }
+    let ancestor = fork.temporal.current().unwrap();
+    match forker.runtime.rollback(&mut grandchild, ancestor) {
+        Ok(()) => println!("Rolled back to version {}: {:?}", ancestor, grandchild.to_array().to_vec()),
+        Err(e) => println!("Rollback failed: {}", e),
+    }
+
+    // Test many-worlds execution of a developmental script
+    let mut runtime = PhylogeneticRuntime::new(0.9);
+    let mut seeds = vec![MorphicTensor::from_data(ndarray::array![0.0, 0.0, 1.0])];
+    let script = [QuantumOperation::H, QuantumOperation::CX, QuantumOperation::Measure];
+    let branches = runtime.execute_in_superposition(&mut seeds, &script);
+    for branch in &branches.branches {
+        println!("  branch outcome {:02b}: amplitude {:.3}, p = {:.3}, data {:?}",
+                 branch.outcome(), branch.amplitude, branch.probability, branch.tensor.to_array().to_vec());
+    }
+    let collapsed = branches.collapse(|t| t.to_array().sum());
+    println!("Collapsed to: {:?} ({:?})", collapsed[0].to_array().to_vec(), collapsed[0].quantum_state);
+
+    // Test a generational population run
+    let mut manager = PopulationManager::new(EnvironmentalSelector::new(PhylogeneticRuntime::new(0.5)),
+                                             &[MorphicTensor::from_data(ndarray::array![2.0, -2.0, 2.0])], 12);
+    manager.set_mutation(MutationSchedule::Adaptive { initial: 0.4, factor: 1.5 });
+    manager.crossover = CrossoverOperator::Arithmetic { alpha: 0.5 };
+    let sphere = |t: &MorphicTensor| -t.to_array().mapv(|x| x * x).sum();
+    let history = manager.run(5, &sphere).to_vec();
+    for stats in &history {
+        println!("  gen {}: best {:.3}, mean {:.3}, σ {:.3}, scale {:.3}",
+                 stats.generation, stats.best, stats.mean, stats.std_dev, stats.mutation_scale);
+    }
+    println!("Best after {} generations: {:?}", manager.generation(), manager.best.as_ref().map(|(_, f)| *f));
+
     println!("✅ Runtime test completed!");
 }
@@ -2,22 +2,342 @@
 #![allow(dead_code)]
 
 use crate::core::tensor::MorphicTensor;
+use crate::phylogenetic::fitness::Fitness;
 use crate::phylogenetic::PhylogeneticRuntime;
+use rand::Rng;
+
+/// A selected tensor with its score
+#[derive(Debug, Clone, PartialEq)]
+pub struct Survivor {
+    pub index: usize,
+    pub fitness: f64,
+}
+
+/// A survivor of multi-objective selection
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoSurvivor {
+    pub index: usize,
+    pub objectives: Vec<f64>,
+    /// Non-domination rank, 0 being the Pareto front
+    pub front: usize,
+    /// Crowding distance within its front (infinite at the boundary)
+    pub crowding: f64,
+}
+
+/// Single-objective selection operators
+///
+/// Non-finite fitness values rank below every finite one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionStrategy {
+    /// Best of `size` uniformly drawn contestants
+    Tournament { size: usize },
+    /// Fitness-proportional, after shifting scores to be positive
+    Roulette,
+    /// Linear ranking: probability proportional to rank, worst = 1
+    Rank,
+    /// Uniform among the best `fraction` of the population
+    Truncation { fraction: f64 },
+}
+
+fn sanitized(score: f64) -> f64 {
+    if score.is_finite() { score } else { f64::MIN }
+}
+
+impl SelectionStrategy {
+    /// Draw one index from `pool` (indices into `scores`), with replacement
+    pub fn pick<R: Rng>(&self, scores: &[f64], pool: &[usize], rng: &mut R) -> Option<usize> {
+        if pool.is_empty() {
+            return None;
+        }
+        let score = |i: usize| sanitized(scores[i]);
+        let chosen = match *self {
+            SelectionStrategy::Tournament { size } => {
+                (0..size.max(1))
+                    .map(|_| pool[rng.gen_range(0..pool.len())])
+                    .max_by(|&a, &b| score(a).total_cmp(&score(b)))
+                    .expect("tournament has at least one contestant")
+            }
+            SelectionStrategy::Roulette => {
+                let finite: Vec<f64> = pool.iter().map(|&i| scores[i]).filter(|s| s.is_finite()).collect();
+                let min = finite.iter().cloned().fold(f64::INFINITY, f64::min);
+                let max = finite.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                // A small floor keeps the worst individual selectable
+                let floor = if max > min { (max - min) * 1e-3 } else { 1.0 };
+                let weights: Vec<f64> = pool.iter()
+                    .map(|&i| if scores[i].is_finite() { scores[i] - min + floor } else { 0.0 })
+                    .collect();
+                pool[weighted_draw(&weights, rng)]
+            }
+            SelectionStrategy::Rank => {
+                let mut order: Vec<usize> = (0..pool.len()).collect();
+                order.sort_by(|&a, &b| score(pool[a]).total_cmp(&score(pool[b])));
+                let mut weights = vec![0.0; pool.len()];
+                for (rank, &position) in order.iter().enumerate() {
+                    weights[position] = (rank + 1) as f64;
+                }
+                pool[weighted_draw(&weights, rng)]
+            }
+            SelectionStrategy::Truncation { fraction } => {
+                let mut sorted = pool.to_vec();
+                sorted.sort_by(|&a, &b| score(b).total_cmp(&score(a)));
+                let cutoff = ((fraction * pool.len() as f64).ceil() as usize).clamp(1, pool.len());
+                sorted[rng.gen_range(0..cutoff)]
+            }
+        };
+        Some(chosen)
+    }
+
+    /// Select `count` distinct indices of `scores`
+    pub fn select<R: Rng>(&self, scores: &[f64], count: usize, rng: &mut R) -> Vec<usize> {
+        let mut pool: Vec<usize> = (0..scores.len()).collect();
+        let mut chosen = Vec::with_capacity(count.min(scores.len()));
+        while chosen.len() < count {
+            let Some(index) = self.pick(scores, &pool, rng) else {
+                break;
+            };
+            pool.retain(|&i| i != index);
+            chosen.push(index);
+        }
+        chosen
+    }
+}
+
+fn weighted_draw<R: Rng>(weights: &[f64], rng: &mut R) -> usize {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return rng.gen_range(0..weights.len());
+    }
+    let mut draw = rng.gen::<f64>() * total;
+    for (i, w) in weights.iter().enumerate() {
+        if draw < *w {
+            return i;
+        }
+        draw -= w;
+    }
+    weights.len() - 1
+}
+
+/// `a` Pareto-dominates `b` when it is no worse in every objective and better in one
+fn dominates(a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y)
+}
+
+/// Fast non-dominated sort: fronts of indices, Pareto front first (all objectives maximised)
+pub fn non_dominated_sort(objectives: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let n = objectives.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut domination_count = vec![0usize; n];
+    for p in 0..n {
+        for q in 0..n {
+            if dominates(&objectives[p], &objectives[q]) {
+                dominated_by[p].push(q);
+            } else if dominates(&objectives[q], &objectives[p]) {
+                domination_count[p] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut current: Vec<usize> = (0..n).filter(|&p| domination_count[p] == 0).collect();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &p in &current {
+            for &q in &dominated_by[p] {
+                domination_count[q] -= 1;
+                if domination_count[q] == 0 {
+                    next.push(q);
+                }
+            }
+        }
+        fronts.push(current);
+        current = next;
+    }
+    fronts
+}
+
+/// Crowding distance of each member of `front`, in the same order
+pub fn crowding_distance(objectives: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    let dims = front.first().map_or(0, |&i| objectives[i].len());
+    let value = |member: usize, m: usize| objectives[front[member]][m];
+    for m in 0..dims {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| value(a, m).total_cmp(&value(b, m)));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distance[first] = f64::INFINITY;
+        distance[last] = f64::INFINITY;
+        let span = value(last, m) - value(first, m);
+        if span <= 0.0 {
+            continue;
+        }
+        for w in order.windows(3) {
+            distance[w[1]] += (value(w[2], m) - value(w[0], m)) / span;
+        }
+    }
+    distance
+}
+
+/// NSGA-II environmental selection of `count` individuals
+///
+/// Whole fronts are kept in order of rank; the front that overflows is truncated by
+/// descending crowding distance to preserve diversity.
+pub fn nsga2(objectives: &[Vec<f64>], count: usize) -> Vec<ParetoSurvivor> {
+    let mut survivors = Vec::with_capacity(count);
+    for (rank, front) in non_dominated_sort(objectives).into_iter().enumerate() {
+        if survivors.len() >= count {
+            break;
+        }
+        let crowding = crowding_distance(objectives, &front);
+        let mut members: Vec<(usize, f64)> = front.into_iter().zip(crowding).collect();
+        members.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (index, crowding) in members.into_iter().take(count - survivors.len()) {
+            survivors.push(ParetoSurvivor {
+                index,
+                objectives: objectives[index].clone(),
+                front: rank,
+                crowding,
+            });
+        }
+    }
+    survivors
+}
 
 pub struct EnvironmentalSelector {
     pub runtime: PhylogeneticRuntime,
+    pub strategy: SelectionStrategy,
+    /// Number of best tensors that always survive
+    pub elitism: usize,
 }
 
 impl EnvironmentalSelector {
     pub fn new(runtime: PhylogeneticRuntime) -> Self {
-        EnvironmentalSelector { runtime }
+        EnvironmentalSelector {
+            runtime,
+            strategy: SelectionStrategy::Tournament { size: 3 },
+            elitism: 1,
+        }
+    }
+
+    /// Survivors per population size: the runtime's selection pressure is the fraction culled
+    pub fn survivor_count(&self, population: usize) -> usize {
+        if population == 0 {
+            return 0;
+        }
+        let survive = (1.0 - self.runtime.selection_pressure).clamp(0.0, 1.0);
+        ((population as f64 * survive).round() as usize).clamp(1, population)
+    }
+
+    /// Score every tensor and select survivors, fittest first
+    ///
+    /// Elites are kept outright and the strategy fills the remaining places. Scores of
+    /// tensors tracked by the runtime's phylogenetic tree are recorded on their
+    /// current version.
+    pub fn select(&mut self, tensors: &[MorphicTensor], fitness: &dyn Fitness) -> Vec<Survivor> {
+        let scores: Vec<f64> = tensors.iter().map(|t| fitness.evaluate(t)).collect();
+        self.select_scored(tensors, &scores)
+    }
+
+    /// Select survivors from precomputed scores, one per tensor
+    pub fn select_scored(&mut self, tensors: &[MorphicTensor], scores: &[f64]) -> Vec<Survivor> {
+        self.select_scored_with(tensors, scores, &mut rand::thread_rng())
+    }
+
+    /// `select_scored` drawing from the caller's random source
+    pub fn select_scored_with<R: Rng>(&mut self, tensors: &[MorphicTensor], scores: &[f64], rng: &mut R) -> Vec<Survivor> {
+        for (tensor, &score) in tensors.iter().zip(scores) {
+            if self.runtime.tree.tracks(tensor) {
+                self.runtime.tree.set_fitness(tensor.temporal.current().expect("tracked"), score);
+            }
+        }
+        let count = self.survivor_count(tensors.len().min(scores.len()));
+
+        let mut ranked: Vec<usize> = (0..tensors.len().min(scores.len())).collect();
+        ranked.sort_by(|&a, &b| sanitized(scores[b]).total_cmp(&sanitized(scores[a])));
+        let mut chosen: Vec<usize> = ranked.iter().copied().take(self.elitism.min(count)).collect();
+
+        let pool: Vec<usize> = ranked.into_iter().skip(chosen.len()).collect();
+        let pool_scores: Vec<f64> = pool.iter().map(|&i| scores[i]).collect();
+        chosen.extend(self.strategy
+            .select(&pool_scores, count - chosen.len(), rng)
+            .into_iter()
+            .map(|p| pool[p]));
+
+        let mut survivors: Vec<Survivor> = chosen.into_iter()
+            .map(|index| Survivor { index, fitness: scores[index] })
+            .collect();
+        survivors.sort_by(|a, b| sanitized(b.fitness).total_cmp(&sanitized(a.fitness)));
+        survivors
+    }
+
+    /// Multi-objective NSGA-II selection over several fitness functions
+    pub fn select_pareto(&self, tensors: &[MorphicTensor], objectives: &[&dyn Fitness]) -> Vec<ParetoSurvivor> {
+        let scores: Vec<Vec<f64>> = tensors.iter()
+            .map(|t| objectives.iter().map(|f| sanitized(f.evaluate(t))).collect())
+            .collect();
+        nsga2(&scores, self.survivor_count(tensors.len()))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_strategies_favour_fitter_individuals() {
+        let scores: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let mut rng = StdRng::seed_from_u64(7);
+        for strategy in [
+            SelectionStrategy::Tournament { size: 4 },
+            SelectionStrategy::Roulette,
+            SelectionStrategy::Rank,
+            SelectionStrategy::Truncation { fraction: 0.3 },
+        ] {
+            let pool: Vec<usize> = (0..10).collect();
+            let mean: f64 = (0..2000)
+                .map(|_| strategy.pick(&scores, &pool, &mut rng).unwrap() as f64)
+                .sum::<f64>() / 2000.0;
+            assert!(mean > 5.5, "{:?} mean pick {}", strategy, mean);
+
+            let selected = strategy.select(&scores, 4, &mut rng);
+            let mut distinct = selected.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(selected.len(), 4);
+            assert_eq!(distinct.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_selector_keeps_elites_and_void_tensors() {
+        let mut selector = EnvironmentalSelector::new(PhylogeneticRuntime::new(0.5));
+        selector.strategy = SelectionStrategy::Roulette;
+        selector.elitism = 1;
+        let tensors: Vec<MorphicTensor> = (0..6).map(|i| {
+            let mut t = MorphicTensor::void();
+            t.spatial.coordinates = [i as f64, 0.0];
+            t
+        }).collect();
+
+        let survivors = selector.select(&tensors, &|t: &MorphicTensor| t.position()[0]);
+        assert_eq!(survivors.len(), 3);
+        assert_eq!(survivors[0], Survivor { index: 5, fitness: 5.0 });
+    }
+
+    #[test]
+    fn test_nsga2_prefers_pareto_front_and_spread() {
+        let objectives = vec![
+            vec![1.0, 5.0], vec![2.0, 4.0], vec![3.0, 3.0], vec![4.0, 1.0],
+            vec![1.0, 1.0], vec![2.0, 2.0], vec![3.1, 2.9],
+        ];
+        let fronts = non_dominated_sort(&objectives);
+        assert_eq!(fronts[0], vec![0, 1, 2, 3, 6]);
 
-    pub fn select(&self, tensors: &[MorphicTensor]) -> Vec<usize> {
-        tensors.iter()
-            .enumerate()
-            .filter(|(_, t)| self.runtime.apply_selection_pressure(t))
-            .map(|(i, _)| i)
-            .collect()
+        let survivors = nsga2(&objectives, 3);
+        let indices: Vec<usize> = survivors.iter().map(|s| s.index).collect();
+        assert!(survivors.iter().all(|s| s.front == 0));
+        // Boundary points are always kept
+        assert!(indices.contains(&0) && indices.contains(&3));
     }
 }
@@ -0,0 +1,131 @@
+// Many-worlds execution of developmental scripts
+#![allow(dead_code)]
+
+use crate::core::tensor::MorphicTensor;
+use crate::phylogenetic::VersionId;
+use crate::quantum::state::QuantumState;
+use nalgebra::Complex;
+use rand::Rng;
+
+/// One world produced by running a script on a tensor
+#[derive(Debug, Clone)]
+pub struct Branch {
+    /// Index of the tensor the branch was forked from
+    pub source: usize,
+    /// Basis state observed at each measurement, the final one last
+    pub outcomes: Vec<usize>,
+    pub amplitude: Complex<f64>,
+    /// Born-rule weight, normalised over the branches of the same source
+    pub probability: f64,
+    pub version: VersionId,
+    pub tensor: MorphicTensor,
+}
+
+impl Branch {
+    /// Final measured basis state
+    pub fn outcome(&self) -> usize {
+        self.outcomes.last().copied().unwrap_or(0)
+    }
+}
+
+/// All branches of a superposed execution, awaiting collapse
+#[derive(Debug, Clone, Default)]
+pub struct BranchSet {
+    pub branches: Vec<Branch>,
+}
+
+impl BranchSet {
+    pub fn len(&self) -> usize {
+        self.branches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// Branches forked from tensor `source`
+    pub fn of_source(&self, source: usize) -> impl Iterator<Item = &Branch> {
+        self.branches.iter().filter(move |b| b.source == source)
+    }
+
+    /// Source indices in ascending order
+    pub fn sources(&self) -> Vec<usize> {
+        let mut sources: Vec<usize> = self.branches.iter().map(|b| b.source).collect();
+        sources.sort_unstable();
+        sources.dedup();
+        sources
+    }
+
+    /// Keep, for each source, the branch maximising probability × fitness
+    pub fn collapse<F>(self, fitness: F) -> Vec<MorphicTensor>
+    where
+        F: Fn(&MorphicTensor) -> f64,
+    {
+        self.collapse_with(|branches| {
+            branches.iter()
+                .enumerate()
+                .map(|(i, b)| (i, b.probability * fitness(&b.tensor)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+                .unwrap_or(0)
+        })
+    }
+
+    /// Sample one branch per source by its Born-rule probability
+    pub fn measure(self) -> Vec<MorphicTensor> {
+        let mut rng = rand::thread_rng();
+        self.collapse_with(|branches| {
+            let draw = rng.gen::<f64>() * branches.iter().map(|b| b.probability).sum::<f64>();
+            let mut cumulative = 0.0;
+            for (i, branch) in branches.iter().enumerate() {
+                cumulative += branch.probability;
+                if draw < cumulative {
+                    return i;
+                }
+            }
+            branches.len() - 1
+        })
+    }
+
+    fn collapse_with<S>(mut self, mut choose: S) -> Vec<MorphicTensor>
+    where
+        S: FnMut(&[Branch]) -> usize,
+    {
+        self.branches.sort_by_key(|b| b.source);
+        self.branches
+            .chunk_by(|a, b| a.source == b.source)
+            .map(|branches| {
+                let mut chosen = branches[choose(branches)].tensor.clone();
+                chosen.quantum_state = QuantumState::Collapsed;
+                chosen
+            })
+            .collect()
+    }
+}
+
+/// Unnormalised branch state during execution: measurement record and amplitudes
+pub(crate) struct World {
+    pub outcomes: Vec<usize>,
+    pub state: Vec<Complex<f64>>,
+}
+
+impl World {
+    /// Project onto each computational basis state with non-negligible weight
+    pub fn split(self, threshold: f64) -> Vec<World> {
+        self.state.iter()
+            .enumerate()
+            .filter(|(_, amplitude)| amplitude.norm_sqr() > threshold)
+            .map(|(index, &amplitude)| {
+                let mut state = vec![Complex::new(0.0, 0.0); self.state.len()];
+                state[index] = amplitude;
+                let mut outcomes = self.outcomes.clone();
+                outcomes.push(index);
+                World { outcomes, state }
+            })
+            .collect()
+    }
+
+    pub fn amplitude(&self) -> Complex<f64> {
+        self.outcomes.last().map(|&i| self.state[i]).unwrap_or(Complex::new(0.0, 0.0))
+    }
+}
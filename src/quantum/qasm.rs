@@ -8,7 +8,11 @@ pub enum QuantumOperation {
     Y, // Pauli-Y gate
     Z, // Pauli-Z gate
     CX, // Controlled-X (CNOT) gate
+    S, // Phase gate
     T, // T gate
+    RX(f64), // Rotation about X by an angle
+    RY(f64), // Rotation about Y by an angle
+    RZ(f64), // Rotation about Z by an angle
     Measure, // Measurement operation
     Custom(String), // Custom operation
 }
@@ -21,7 +25,11 @@ impl QuantumOperation {
             QuantumOperation::Y => "y".to_string(),
             QuantumOperation::Z => "z".to_string(),
             QuantumOperation::CX => "cx".to_string(),
+            QuantumOperation::S => "s".to_string(),
             QuantumOperation::T => "t".to_string(),
+            QuantumOperation::RX(theta) => format!("rx({})", theta),
+            QuantumOperation::RY(theta) => format!("ry({})", theta),
+            QuantumOperation::RZ(theta) => format!("rz({})", theta),
             QuantumOperation::Measure => "measure".to_string(),
             QuantumOperation::Custom(op) => op.clone(),
         }
@@ -1,6 +1,8 @@
 // Quantum state representation with Void state
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum QuantumState {
     Void,
     Superposition,
@@ -4,6 +4,8 @@
 use crate::core::tensor::MorphicTensor;
 use crate::quantum::state::QuantumState;
 use crate::quantum::qasm::QuantumOperation;
+use crate::evolutionary::BlackBoxOptimizer;
+use crate::quantum_simulation::Vqe;
 use rand::Rng;
 
 pub struct HybridComputation {
@@ -30,6 +32,27 @@ impl HybridComputation {
         }
     }
 
+    /// Ground-state energy of a Hamiltonian: VQE on the quantum path, exact
+    /// diagonalisation as the classical fallback
+    ///
+    /// Returns `None` when the tensor stays below the quantum threshold and there is no
+    /// classical fallback.
+    pub fn ground_state_energy(&self, tensor: &mut MorphicTensor, vqe: &Vqe, optimizer: &mut dyn BlackBoxOptimizer) -> Result<Option<f64>, String> {
+        if self.should_use_quantum(tensor) {
+            println!("Performing variational quantum eigensolve");
+            let energy = vqe.run(optimizer)?.energy;
+            tensor.quantum_state = QuantumState::Superposition;
+            Ok(Some(energy))
+        } else if self.classical_fallback {
+            println!("Falling back to exact diagonalisation");
+            let energy = vqe.hamiltonian.ground_energy(vqe.ansatz.qubits)?;
+            tensor.quantum_state = QuantumState::Collapsed;
+            Ok(Some(energy))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn should_use_quantum(&self, tensor: &MorphicTensor) -> bool {
         tensor.entanglement.strength > self.quantum_threshold
     }
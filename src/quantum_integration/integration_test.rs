@@ -53,7 +53,7 @@ fn main() {
     println!("Generated QASM code:\n{}", qasm_code);
 
     // Test memory storage with Void state
-    let memory = QuantumMemoryManager::new(100);
+    let mut memory = QuantumMemoryManager::new(100);
     memory.store(&tensor1);
     memory.store(&MorphicTensor::void());
 
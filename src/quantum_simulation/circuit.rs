@@ -0,0 +1,152 @@
+// Gate-level circuits for the simulators
+#![allow(dead_code)]
+
+use crate::quantum::qasm::QuantumOperation;
+
+/// One operation addressed to specific qubits
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub operation: QuantumOperation,
+    pub target: usize,
+    pub control: Option<usize>,
+    /// Classical bit receiving a measurement outcome
+    pub clbit: Option<usize>,
+}
+
+/// Ordered list of instructions on a register of `qubits`, recording
+/// measurements into `clbits` classical bits
+#[derive(Debug, Clone, Default)]
+pub struct Circuit {
+    pub qubits: usize,
+    pub clbits: usize,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Circuit {
+    pub fn new(qubits: usize) -> Self {
+        Circuit {
+            qubits,
+            clbits: qubits,
+            instructions: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    pub fn push(&mut self, operation: QuantumOperation, target: usize, control: Option<usize>) -> &mut Self {
+        self.instructions.push(Instruction { operation, target, control, clbit: None });
+        self
+    }
+
+    pub fn h(&mut self, qubit: usize) -> &mut Self {
+        self.push(QuantumOperation::H, qubit, None)
+    }
+
+    pub fn x(&mut self, qubit: usize) -> &mut Self {
+        self.push(QuantumOperation::X, qubit, None)
+    }
+
+    pub fn y(&mut self, qubit: usize) -> &mut Self {
+        self.push(QuantumOperation::Y, qubit, None)
+    }
+
+    pub fn z(&mut self, qubit: usize) -> &mut Self {
+        self.push(QuantumOperation::Z, qubit, None)
+    }
+
+    pub fn s(&mut self, qubit: usize) -> &mut Self {
+        self.push(QuantumOperation::S, qubit, None)
+    }
+
+    pub fn t(&mut self, qubit: usize) -> &mut Self {
+        self.push(QuantumOperation::T, qubit, None)
+    }
+
+    pub fn rx(&mut self, qubit: usize, theta: f64) -> &mut Self {
+        self.push(QuantumOperation::RX(theta), qubit, None)
+    }
+
+    pub fn ry(&mut self, qubit: usize, theta: f64) -> &mut Self {
+        self.push(QuantumOperation::RY(theta), qubit, None)
+    }
+
+    pub fn rz(&mut self, qubit: usize, theta: f64) -> &mut Self {
+        self.push(QuantumOperation::RZ(theta), qubit, None)
+    }
+
+    pub fn cx(&mut self, control: usize, target: usize) -> &mut Self {
+        self.push(QuantumOperation::CX, target, Some(control))
+    }
+
+    /// exp(−iθ Z_a Z_b / 2) as CX · RZ · CX
+    pub fn rzz(&mut self, a: usize, b: usize, theta: f64) -> &mut Self {
+        self.cx(a, b).rz(b, theta).cx(a, b)
+    }
+
+    /// Measure `qubit` into the classical bit of the same index
+    pub fn measure(&mut self, qubit: usize) -> &mut Self {
+        self.measure_into(qubit, qubit)
+    }
+
+    pub fn measure_into(&mut self, qubit: usize, clbit: usize) -> &mut Self {
+        self.clbits = self.clbits.max(clbit + 1);
+        self.instructions.push(Instruction { operation: QuantumOperation::Measure, target: qubit, control: None, clbit: Some(clbit) });
+        self
+    }
+
+    pub fn measure_all(&mut self) -> &mut Self {
+        for qubit in 0..self.qubits {
+            self.measure(qubit);
+        }
+        self
+    }
+
+    /// Whether every operation is a Clifford gate or a measurement
+    pub fn is_clifford(&self) -> bool {
+        self.instructions.iter().all(|i| matches!(
+            i.operation,
+            QuantumOperation::H | QuantumOperation::S | QuantumOperation::X | QuantumOperation::Y
+                | QuantumOperation::Z | QuantumOperation::CX | QuantumOperation::Measure
+        ))
+    }
+
+    /// Whether no gate follows the first measurement, so every shot shares one
+    /// pre-measurement state
+    pub fn has_terminal_measurements(&self) -> bool {
+        self.instructions.iter()
+            .skip_while(|i| !matches!(i.operation, QuantumOperation::Measure))
+            .all(|i| matches!(i.operation, QuantumOperation::Measure))
+    }
+
+    /// Append all instructions of `other`
+    pub fn extend(&mut self, other: &Circuit) -> &mut Self {
+        self.qubits = self.qubits.max(other.qubits);
+        self.clbits = self.clbits.max(other.clbits);
+        self.instructions.extend(other.instructions.iter().cloned());
+        self
+    }
+
+    /// OpenQASM 3 source for the circuit
+    pub fn to_qasm(&self) -> String {
+        let mut qasm = format!("OPENQASM 3.0;\ninclude \"stdgates.inc\";\nqubit[{}] q;\nbit[{}] c;\n", self.qubits, self.clbits);
+        for instruction in &self.instructions {
+            let line = match (&instruction.operation, instruction.control) {
+                (QuantumOperation::Measure, _) => match instruction.clbit {
+                    Some(clbit) => format!("c[{}] = measure q[{}];", clbit, instruction.target),
+                    None => format!("measure q[{}];", instruction.target),
+                },
+                (operation, Some(control)) => format!("{} q[{}], q[{}];", operation.to_qasm(), control, instruction.target),
+                (operation, None) => format!("{} q[{}];", operation.to_qasm(), instruction.target),
+            };
+            qasm.push_str(&line);
+            qasm.push('\n');
+        }
+        qasm
+    }
+}
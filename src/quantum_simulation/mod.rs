@@ -5,13 +5,52 @@ use crate::core::tensor::MorphicTensor;
 use crate::quantum::state::QuantumState;
 use crate::quantum::qasm::QuantumOperation;
 use nalgebra::{DMatrix, Complex};
+use rand::rngs::ThreadRng;
 use rand::Rng;
 use std::f64::consts::PI;
 
+pub mod circuit;
+pub mod mps;
+pub mod observable;
+pub mod sampling;
+pub mod sharded;
+pub mod stabilizer;
+pub mod variational;
+
+pub use circuit::{Circuit, Instruction};
+pub use mps::MatrixProductState;
+pub use sampling::Counts;
+pub use sharded::ShardedResult;
+pub use stabilizer::StabilizerState;
+pub use observable::{Estimate, MeasurementGroup, Observable, Pauli, PauliString};
+pub use variational::{HardwareEfficientAnsatz, MaxCut, Qaoa, QaoaResult, Vqe, VqeResult};
+
+/// Representation used to execute a circuit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    /// Dense 2ⁿ amplitudes; any gate and the noise model
+    StateVector,
+    /// CHP tableau; Clifford circuits on thousands of qubits
+    Stabilizer,
+    /// Truncated MPS; low-entanglement circuits on tens to hundreds of qubits
+    MatrixProductState,
+}
+
+/// Largest register simulated with dense amplitudes when the backend is automatic
+pub const DENSE_QUBIT_LIMIT: usize = 24;
+
 pub struct QuantumSimulator {
     pub qubit_count: usize,
     pub decoherence_rate: f64,
     pub gate_fidelity: f64,
+    /// Probabilities of reading a 0 as 1 and a 1 as 0
+    pub readout_error: (f64, f64),
+    /// Backend forced for `run`; chosen per circuit when `None`
+    pub backend: Option<Backend>,
+    /// Bond dimension cap of the matrix-product backend
+    pub max_bond: usize,
+    /// Discarded Schmidt weight the matrix-product backend may spend on gates
+    pub truncation_budget: f64,
 }
 
 impl QuantumSimulator {
@@ -20,7 +59,229 @@ impl QuantumSimulator {
             qubit_count: qubits,
             decoherence_rate: 0.01,
             gate_fidelity: 0.99,
+            readout_error: (0.0, 0.0),
+            backend: None,
+            max_bond: 64,
+            truncation_budget: 1e-8,
+        }
+    }
+
+    /// Simulator without gate errors or decoherence
+    pub fn ideal(qubits: usize) -> Self {
+        QuantumSimulator {
+            qubit_count: qubits,
+            decoherence_rate: 0.0,
+            gate_fidelity: 1.0,
+            readout_error: (0.0, 0.0),
+            backend: None,
+            max_bond: 64,
+            truncation_budget: 1e-8,
+        }
+    }
+
+    /// The all-zero basis state |0…0⟩
+    pub fn zero_state(&self) -> Vec<Complex<f64>> {
+        let mut state = vec![Complex::new(0.0, 0.0); 1usize << self.qubit_count];
+        state[0] = Complex::new(1.0, 0.0);
+        state
+    }
+
+    /// Apply every instruction of a circuit in order and return the classical register
+    ///
+    /// Measurements collapse only the measured qubit, leaving the post-measurement
+    /// state for later gates, and are recorded through the readout error.
+    pub fn execute(&self, circuit: &Circuit, state: &mut [Complex<f64>]) -> Vec<bool> {
+        let mut rng = rand::thread_rng();
+        let mut register = vec![false; circuit.clbits];
+        for instruction in &circuit.instructions {
+            match instruction.operation {
+                QuantumOperation::Measure => {
+                    let outcome = self.measure_qubit(state, instruction.target);
+                    if let Some(bit) = instruction.clbit.and_then(|c| register.get_mut(c)) {
+                        *bit = self.read_out(outcome, &mut rng);
+                    }
+                }
+                ref gate => self.apply_gate(state, gate, instruction.target, instruction.control),
+            }
+        }
+        register
+    }
+
+    /// The configured backend, or else: noise-free Clifford circuits go to the
+    /// stabilizer tableau, registers too large for dense amplitudes to a
+    /// matrix-product state, everything else to the state vector
+    pub fn select_backend(&self, circuit: &Circuit) -> Backend {
+        if let Some(backend) = self.backend {
+            return backend;
+        }
+        let ideal = self.gate_fidelity >= 1.0 && self.decoherence_rate <= 0.0;
+        if ideal && circuit.is_clifford() {
+            Backend::Stabilizer
+        } else if circuit.qubits > DENSE_QUBIT_LIMIT {
+            Backend::MatrixProductState
+        } else {
+            Backend::StateVector
+        }
+    }
+
+    /// Execute a circuit `shots` times from |0…0⟩ and histogram the classical register
+    ///
+    /// Noise-free circuits whose measurements all come last are simulated once and
+    /// sampled; otherwise every shot runs the full circuit. The circuit must be as
+    /// wide as the simulator and address only its own qubits.
+    pub fn run(&self, circuit: &Circuit, shots: usize) -> Result<Counts, String> {
+        if circuit.qubits != self.qubit_count {
+            return Err(format!("A {}-qubit circuit cannot run on a {}-qubit simulator", circuit.qubits, self.qubit_count));
+        }
+        if let Some(instruction) = circuit.instructions.iter()
+            .find(|i| i.target >= circuit.qubits || i.control.is_some_and(|c| c >= circuit.qubits))
+        {
+            return Err(format!("{:?} addresses qubits outside the {}-qubit register", instruction.operation, circuit.qubits));
+        }
+        let backend = self.select_backend(circuit);
+        println!("Running {}-qubit circuit for {} shots on the {:?} backend", circuit.qubits, shots, backend);
+        match backend {
+            Backend::Stabilizer => {
+                if !circuit.is_clifford() {
+                    return Err("The stabilizer backend only runs Clifford circuits".to_string());
+                }
+                let (counts, _) = self.run_shots(circuit, shots, StabilizerState::new(circuit.qubits), |state, instruction, rng| {
+                    state.apply(instruction, rng)
+                })?;
+                return Ok(counts);
+            }
+            Backend::MatrixProductState => {
+                let mps = MatrixProductState::new(circuit.qubits, self.max_bond, self.truncation_budget);
+                let (counts, prepared) = self.run_shots(circuit, shots, mps, |state, instruction, rng| self.apply_mps(state, instruction, rng))?;
+                println!("Matrix-product state: max bond {}, truncation error {:.2e}",
+                         prepared.bond_dimensions().into_iter().max().unwrap_or(1), prepared.truncation_error);
+                if !prepared.within_budget() {
+                    println!("Truncation error exceeds the budget of {:.2e}; raise max_bond", prepared.error_budget);
+                }
+                return Ok(counts);
+            }
+            Backend::StateVector => {}
         }
+        let mut counts = Counts::new(circuit.clbits);
+        let ideal = self.gate_fidelity >= 1.0 && self.decoherence_rate <= 0.0;
+        if ideal && circuit.has_terminal_measurements() {
+            let mut state = self.zero_state();
+            let measurements: Vec<(usize, Option<usize>)> = circuit.instructions.iter()
+                .filter(|i| matches!(i.operation, QuantumOperation::Measure))
+                .map(|i| (i.target, i.clbit))
+                .collect();
+            for instruction in circuit.instructions.iter().filter(|i| !matches!(i.operation, QuantumOperation::Measure)) {
+                self.apply_gate(&mut state, &instruction.operation, instruction.target, instruction.control);
+            }
+            let cumulative: Vec<f64> = state.iter()
+                .scan(0.0, |total, a| {
+                    *total += a.norm_sqr();
+                    Some(*total)
+                })
+                .collect();
+            let norm = cumulative.last().copied().unwrap_or(0.0);
+            let mut rng = rand::thread_rng();
+            for _ in 0..shots {
+                let draw = rng.gen::<f64>() * norm;
+                let outcome = cumulative.partition_point(|&c| c < draw).min(cumulative.len() - 1);
+                let mut register = vec![false; circuit.clbits];
+                for &(qubit, clbit) in &measurements {
+                    if let Some(bit) = clbit.and_then(|c| register.get_mut(c)) {
+                        *bit = self.read_out(outcome >> qubit & 1 == 1, &mut rng);
+                    }
+                }
+                counts.record(&register);
+            }
+        } else {
+            for _ in 0..shots {
+                let mut state = self.zero_state();
+                counts.record(&self.execute(circuit, &mut state));
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Shots on a clonable backend; gates before the first measurement are applied once
+    /// and the prepared state is returned with the counts
+    ///
+    /// The first instruction the backend rejects aborts the run.
+    fn run_shots<S, F>(&self, circuit: &Circuit, shots: usize, mut prepared: S, apply: F) -> Result<(Counts, S), String>
+    where
+        S: Clone,
+        F: Fn(&mut S, &Instruction, &mut ThreadRng) -> Result<Option<bool>, String>,
+    {
+        let mut rng = rand::thread_rng();
+        let mut counts = Counts::new(circuit.clbits);
+        let split = circuit.instructions.iter()
+            .position(|i| matches!(i.operation, QuantumOperation::Measure))
+            .unwrap_or(circuit.instructions.len());
+        let (prefix, rest) = circuit.instructions.split_at(split);
+        for instruction in prefix {
+            apply(&mut prepared, instruction, &mut rng)?;
+        }
+        for _ in 0..shots {
+            let mut state = prepared.clone();
+            let mut register = vec![false; circuit.clbits];
+            for instruction in rest {
+                if let Some(outcome) = apply(&mut state, instruction, &mut rng)? {
+                    if let Some(bit) = instruction.clbit.and_then(|c| register.get_mut(c)) {
+                        *bit = self.read_out(outcome, &mut rng);
+                    }
+                }
+            }
+            counts.record(&register);
+        }
+        Ok((counts, prepared))
+    }
+
+    /// One instruction on a matrix-product state
+    fn apply_mps(&self, mps: &mut MatrixProductState, instruction: &Instruction, rng: &mut ThreadRng) -> Result<Option<bool>, String> {
+        let target = instruction.target;
+        if target >= mps.qubits || instruction.control.is_some_and(|c| c >= mps.qubits || c == target) {
+            return Err(format!("{:?} addresses qubits outside the {}-qubit chain", instruction.operation, mps.qubits));
+        }
+        match (&instruction.operation, instruction.control) {
+            (QuantumOperation::Measure, _) => return Ok(Some(mps.measure(target, rng))),
+            (QuantumOperation::CX, Some(control)) => mps.cx(control, target),
+            (QuantumOperation::CX, None) | (QuantumOperation::Custom(_), _) => {
+                return Err(format!("{:?} is not supported on the matrix-product backend", instruction.operation));
+            }
+            (gate, _) => mps.apply_single(target, &self.single_qubit_matrix(gate)),
+        }
+        Ok(None)
+    }
+
+    /// Projectively measure one qubit, renormalising the remaining amplitudes
+    pub fn measure_qubit(&self, state: &mut [Complex<f64>], qubit: usize) -> bool {
+        let mask = 1usize << qubit;
+        let (mut p0, mut p1) = (0.0, 0.0);
+        for (i, amplitude) in state.iter().enumerate() {
+            if i & mask == 0 {
+                p0 += amplitude.norm_sqr();
+            } else {
+                p1 += amplitude.norm_sqr();
+            }
+        }
+        let outcome = rand::thread_rng().gen::<f64>() * (p0 + p1) >= p0;
+        let kept = if outcome { p1 } else { p0 };
+        let scale = if kept > 0.0 { 1.0 / kept.sqrt() } else { 0.0 };
+        for (i, amplitude) in state.iter_mut().enumerate() {
+            *amplitude = if (i & mask != 0) == outcome { *amplitude * scale } else { Complex::new(0.0, 0.0) };
+        }
+        outcome
+    }
+
+    /// Classical record of a measured bit after readout error
+    fn read_out<R: Rng>(&self, outcome: bool, rng: &mut R) -> bool {
+        let flip = if outcome { self.readout_error.1 } else { self.readout_error.0 };
+        if flip > 0.0 && rng.gen::<f64>() < flip { !outcome } else { outcome }
+    }
+
+    /// Final state of a circuit run from |0…0⟩
+    pub fn statevector(&self, circuit: &Circuit) -> Vec<Complex<f64>> {
+        let mut state = self.zero_state();
+        self.execute(circuit, &mut state);
+        state
     }
 
     /// Initialize a quantum state for simulation
@@ -44,26 +305,66 @@ impl QuantumSimulator {
     }
 
     /// Apply a quantum gate to the state vector
-    pub fn apply_gate(&self, state: &mut Vec<Complex<f64>>, gate: &QuantumOperation, _target: usize, _control: Option<usize>) {
+    ///
+    /// Qubit `q` is bit `q` of the basis-state index. `CX` flips `target` when
+    /// `control` is set and is ignored without a control qubit.
+    pub fn apply_gate(&self, state: &mut [Complex<f64>], gate: &QuantumOperation, target: usize, control: Option<usize>) {
+        if target >= self.qubit_count || control.is_some_and(|c| c >= self.qubit_count || c == target) {
+            println!("Gate {:?} addresses qubits outside the register", gate);
+            return;
+        }
         let gate_matrix = match gate {
+            QuantumOperation::CX => match control {
+                Some(control) => {
+                    self.apply_controlled_x(state, control, target);
+                    return;
+                }
+                None => {
+                    println!("CX needs a control qubit");
+                    return;
+                }
+            },
+            single => self.single_qubit_matrix(single),
+        };
+
+        // Apply gate with potential noise
+        self.apply_matrix(state, &gate_matrix, target);
+    }
+
+    /// 2×2 unitary of a single-qubit gate; anything else acts as the identity
+    pub(crate) fn single_qubit_matrix(&self, gate: &QuantumOperation) -> DMatrix<Complex<f64>> {
+        match gate {
             QuantumOperation::H => self.hadamard_matrix(),
             QuantumOperation::X => self.pauli_x_matrix(),
             QuantumOperation::Y => self.pauli_y_matrix(),
             QuantumOperation::Z => self.pauli_z_matrix(),
-            QuantumOperation::CX => self.cnot_matrix(),
+            QuantumOperation::S => self.s_gate_matrix(),
             QuantumOperation::T => self.t_gate_matrix(),
+            QuantumOperation::RX(theta) => self.rotation_matrix(&self.pauli_x_matrix(), *theta),
+            QuantumOperation::RY(theta) => self.rotation_matrix(&self.pauli_y_matrix(), *theta),
+            QuantumOperation::RZ(theta) => self.rotation_matrix(&self.pauli_z_matrix(), *theta),
             _ => self.identity_matrix(),
-        };
-
-        // Apply gate with potential noise
-        self.apply_matrix(state, &gate_matrix);
+        }
     }
 
-    fn apply_matrix(&self, state: &mut Vec<Complex<f64>>, matrix: &DMatrix<Complex<f64>>) {
-        // Simplified gate application - in a real simulator this would use efficient state vector manipulation
-        let dim = state.len();
-        let mut new_state = vec![Complex::new(0.0, 0.0); dim];
+    /// Apply a single-qubit gate to every qubit, or CX along the chain 0→1→…→n−1
+    pub fn apply_transversal(&self, state: &mut [Complex<f64>], gate: &QuantumOperation) {
+        match gate {
+            QuantumOperation::CX => {
+                for qubit in 1..self.qubit_count {
+                    self.apply_gate(state, gate, qubit, Some(qubit - 1));
+                }
+            }
+            QuantumOperation::Measure => {}
+            _ => {
+                for qubit in 0..self.qubit_count {
+                    self.apply_gate(state, gate, qubit, None);
+                }
+            }
+        }
+    }
 
+    fn apply_matrix(&self, state: &mut [Complex<f64>], matrix: &DMatrix<Complex<f64>>, target: usize) {
         // Apply gate with potential fidelity loss
         let mut rng = rand::thread_rng();
         let fidelity_effect = if rng.gen::<f64>() > self.gate_fidelity {
@@ -73,21 +374,32 @@ impl QuantumSimulator {
             1.0 // Perfect application
         };
 
-        // This is a naive O(2^n) implementation - suitable for small qubit counts
-        for i in 0..dim {
-            for j in 0..dim {
-                let amplitude = matrix[(i % 2, j % 2)] * fidelity_effect;
-                new_state[i] += state[j] * amplitude;
-            }
+        // Mix each pair of amplitudes that differ only in the target bit
+        let mask = 1usize << target;
+        for i in (0..state.len()).filter(|i| i & mask == 0) {
+            let (a0, a1) = (state[i], state[i | mask]);
+            state[i] = (matrix[(0, 0)] * a0 + matrix[(0, 1)] * a1) * fidelity_effect;
+            state[i | mask] = (matrix[(1, 0)] * a0 + matrix[(1, 1)] * a1) * fidelity_effect;
         }
 
         // Apply decoherence
-        self.apply_decoherence(&mut new_state);
+        self.apply_decoherence(state);
+    }
 
-        *state = new_state;
+    fn apply_controlled_x(&self, state: &mut [Complex<f64>], control: usize, target: usize) {
+        let (control_mask, target_mask) = (1usize << control, 1usize << target);
+        for i in 0..state.len() {
+            if i & control_mask != 0 && i & target_mask == 0 {
+                state.swap(i, i | target_mask);
+            }
+        }
+        self.apply_decoherence(state);
     }
 
     fn apply_decoherence(&self, state: &mut [Complex<f64>]) {
+        if self.decoherence_rate <= 0.0 {
+            return;
+        }
         let mut rng = rand::thread_rng();
         for amplitude in state.iter_mut() {
             if rng.gen::<f64>() < self.decoherence_rate {
@@ -165,10 +477,23 @@ impl QuantumSimulator {
         ])
     }
 
+    /// exp(−iθP/2) for a Pauli matrix P
+    fn rotation_matrix(&self, pauli: &DMatrix<Complex<f64>>, theta: f64) -> DMatrix<Complex<f64>> {
+        let identity: DMatrix<Complex<f64>> = DMatrix::identity(2, 2);
+        identity * Complex::new((theta / 2.0).cos(), 0.0) - pauli * Complex::new(0.0, (theta / 2.0).sin())
+    }
+
+    fn s_gate_matrix(&self) -> DMatrix<Complex<f64>> {
+        DMatrix::from_row_slice(2, 2, &[
+            Complex::new(1.0, 0.0), Complex::new(0.0, 0.0),
+            Complex::new(0.0, 0.0), Complex::new(0.0, 1.0)
+        ])
+    }
+
     fn t_gate_matrix(&self) -> DMatrix<Complex<f64>> {
         DMatrix::from_row_slice(2, 2, &[
             Complex::new(1.0, 0.0), Complex::new(0.0, 0.0),
-            Complex::new(0.0, 0.0), Complex::new((PI/8.0).cos(), (PI/8.0).sin())
+            Complex::new(0.0, 0.0), Complex::new((PI/4.0).cos(), (PI/4.0).sin())
         ])
     }
 }
@@ -186,13 +511,63 @@ impl DistributedSimulator {
         }
     }
 
+    /// Total register size across all nodes
+    pub fn total_qubits(&self) -> usize {
+        self.node_count * self.qubits_per_node
+    }
+
+    /// Run a circuit on a state vector sharded by its top qubits, one worker thread per node
+    ///
+    /// Gates on the lower qubits act in place on each shard; gates targeting a top
+    /// qubit first trade half of each shard with the partner node over a channel.
+    pub fn simulate_circuit(&self, circuit: &Circuit) -> Result<ShardedResult, String> {
+        if circuit.qubits > self.total_qubits() {
+            return Err(format!("Circuit needs {} qubits but the cluster holds {}", circuit.qubits, self.total_qubits()));
+        }
+        let mut padded = circuit.clone();
+        padded.qubits = self.total_qubits();
+        sharded::run_sharded(&padded, self.node_count)
+    }
+
     /// Simulate distributed quantum computation
-    pub fn simulate_distributed(&self, tensor: &MorphicTensor, _operations: &[QuantumOperation]) {
+    ///
+    /// Operations are applied transversally (single-qubit gates on every qubit, CX
+    /// along the qubit chain) to a register seeded from the tensor's quantum state.
+    pub fn simulate_distributed(&self, tensor: &MorphicTensor, operations: &[QuantumOperation]) {
         println!("Simulating distributed quantum computation across {} nodes", self.node_count);
-        println!("Total simulated qubits: {}", self.node_count * self.qubits_per_node);
+        println!("Total simulated qubits: {}", self.total_qubits());
 
-        // This is a placeholder for future distributed simulation logic
-        // In a real implementation, this would manage node communication and task distribution
+        let qubits = self.total_qubits();
+        let mut circuit = Circuit::new(qubits);
+        if tensor.quantum_state == QuantumState::Superposition {
+            for qubit in 0..qubits {
+                circuit.h(qubit);
+            }
+        }
+        for operation in operations {
+            match operation {
+                QuantumOperation::CX => {
+                    for qubit in 1..qubits {
+                        circuit.cx(qubit - 1, qubit);
+                    }
+                }
+                QuantumOperation::Measure | QuantumOperation::Custom(_) => {}
+                gate => {
+                    for qubit in 0..qubits {
+                        circuit.push(gate.clone(), qubit, None);
+                    }
+                }
+            }
+        }
+
+        match self.simulate_circuit(&circuit) {
+            Ok(result) => {
+                let (peak, probability) = result.peak().unwrap_or((0, 0.0));
+                println!("{} exchanges moved {} amplitudes; most likely state {} (p = {:.4})",
+                         result.exchanges, result.amplitudes_sent, peak, probability);
+            }
+            Err(e) => println!("Distributed simulation failed: {}", e),
+        }
 
         println!("Distributed simulation completed with tensor entanglement strength: {:.2}",
                  tensor.entanglement.strength);
@@ -0,0 +1,310 @@
+// Matrix-product-state simulation of low-entanglement circuits
+#![allow(dead_code)]
+
+use nalgebra::{Complex, DMatrix};
+use rand::Rng;
+
+type Matrix = DMatrix<Complex<f64>>;
+
+/// Chain of site tensors, one per qubit, with `A[q][s]` the χ_left × χ_right matrix
+/// for qubit `q` in basis state `s`
+///
+/// The chain is kept in mixed-canonical form around `center`, so the singular values
+/// of every two-site split are the true Schmidt coefficients and the weight discarded
+/// by truncation is the exact loss of fidelity.
+#[derive(Debug, Clone)]
+pub struct MatrixProductState {
+    pub qubits: usize,
+    /// Largest bond dimension kept after a two-qubit gate
+    pub max_bond: usize,
+    /// Total discarded weight the run may spend on truncations
+    pub error_budget: f64,
+    /// Discarded weight accumulated so far
+    pub truncation_error: f64,
+    sites: Vec<[Matrix; 2]>,
+    center: usize,
+}
+
+impl MatrixProductState {
+    /// The product state |0…0⟩ with every bond of dimension one
+    pub fn new(qubits: usize, max_bond: usize, error_budget: f64) -> Self {
+        let zero = Matrix::from_element(1, 1, Complex::new(0.0, 0.0));
+        let one = Matrix::from_element(1, 1, Complex::new(1.0, 0.0));
+        MatrixProductState {
+            qubits,
+            max_bond: max_bond.max(1),
+            error_budget: error_budget.max(0.0),
+            truncation_error: 0.0,
+            sites: vec![[one, zero]; qubits],
+            center: 0,
+        }
+    }
+
+    /// Dimension of the bond to the right of each site but the last
+    pub fn bond_dimensions(&self) -> Vec<usize> {
+        self.sites.iter().take(self.qubits.saturating_sub(1)).map(|site| site[0].ncols()).collect()
+    }
+
+    pub fn within_budget(&self) -> bool {
+        self.truncation_error <= self.error_budget
+    }
+
+    /// ⟨index|ψ⟩ with qubit `q` as bit `q` of the index
+    pub fn amplitude(&self, index: usize) -> Complex<f64> {
+        let product = self.sites.iter()
+            .enumerate()
+            .fold(Matrix::identity(1, 1), |acc, (q, site)| acc * &site[index >> q & 1]);
+        product[(0, 0)]
+    }
+
+    /// Dense amplitudes; only sensible for small registers
+    pub fn statevector(&self) -> Vec<Complex<f64>> {
+        (0..1usize << self.qubits).map(|index| self.amplitude(index)).collect()
+    }
+
+    pub fn apply_single(&mut self, qubit: usize, gate: &Matrix) {
+        let site = &self.sites[qubit];
+        let updated = [
+            &site[0] * gate[(0, 0)] + &site[1] * gate[(0, 1)],
+            &site[0] * gate[(1, 0)] + &site[1] * gate[(1, 1)],
+        ];
+        self.sites[qubit] = updated;
+    }
+
+    /// Apply a 4×4 gate whose row index is `2·bit(first) + bit(second)`, routing the
+    /// qubits next to each other with SWAPs when they are not neighbours
+    pub fn apply_two(&mut self, first: usize, second: usize, gate: &Matrix) {
+        if first == second {
+            return;
+        }
+        // Reorder the gate so the lower qubit is the leading index
+        let (low, high, gate) = if first < second {
+            (first, second, gate.clone())
+        } else {
+            let swap = swap_matrix();
+            (second, first, &swap * gate * &swap)
+        };
+        let swap = swap_matrix();
+        for site in (low + 1..high).rev() {
+            self.apply_adjacent(site, &swap);
+        }
+        self.apply_adjacent(low, &gate);
+        for site in low + 1..high {
+            self.apply_adjacent(site, &swap);
+        }
+    }
+
+    pub fn cx(&mut self, control: usize, target: usize) {
+        let mut gate = Matrix::zeros(4, 4);
+        // Control is the leading index: |10⟩ ↔ |11⟩
+        for (row, col) in [(0, 0), (1, 1), (2, 3), (3, 2)] {
+            gate[(row, col)] = Complex::new(1.0, 0.0);
+        }
+        self.apply_two(control, target, &gate);
+    }
+
+    /// Projectively measure `qubit`, leaving the normalised post-measurement state
+    pub fn measure<R: Rng>(&mut self, qubit: usize, rng: &mut R) -> bool {
+        self.move_center(qubit);
+        let site = &self.sites[qubit];
+        let (p0, p1) = (site[0].norm_squared(), site[1].norm_squared());
+        let outcome = rng.gen::<f64>() * (p0 + p1) >= p0;
+        let kept = if outcome { p1 } else { p0 };
+        let scale = if kept > 0.0 { 1.0 / kept.sqrt() } else { 0.0 };
+        let (keep, drop) = if outcome { (1, 0) } else { (0, 1) };
+        let site = &mut self.sites[qubit];
+        site[keep] *= Complex::new(scale, 0.0);
+        site[drop].fill(Complex::new(0.0, 0.0));
+        outcome
+    }
+
+    /// Two-site gate on `site` and `site + 1`, split back by a truncated SVD
+    fn apply_adjacent(&mut self, site: usize, gate: &Matrix) {
+        self.move_center(site);
+        let (left, right) = (self.sites[site][0].nrows(), self.sites[site + 1][0].ncols());
+        let pairs: Vec<Matrix> = (0..4).map(|k| &self.sites[site][k >> 1] * &self.sites[site + 1][k & 1]).collect();
+
+        // θ with rows (s₁, a) and columns (s₂, b)
+        let mut theta = Matrix::zeros(2 * left, 2 * right);
+        for k in 0..4 {
+            let mut block = Matrix::zeros(left, right);
+            for (t, pair) in pairs.iter().enumerate() {
+                if gate[(k, t)] != Complex::new(0.0, 0.0) {
+                    block += pair * gate[(k, t)];
+                }
+            }
+            theta.view_mut(((k >> 1) * left, (k & 1) * right), (left, right)).copy_from(&block);
+        }
+
+        let svd = theta.svd(true, true);
+        let (u, v_t) = (svd.u.expect("left vectors requested"), svd.v_t.expect("right vectors requested"));
+        let weights: Vec<f64> = svd.singular_values.iter().map(|s| s * s).collect();
+        let total: f64 = weights.iter().sum();
+        let keep = self.truncate(&weights, total);
+        let kept: f64 = weights[..keep].iter().sum();
+        let renormalise = if kept > 0.0 { (total / kept).sqrt() } else { 1.0 };
+
+        let mut right_factor = v_t.rows(0, keep).into_owned();
+        for (row, sigma) in svd.singular_values.iter().take(keep).enumerate() {
+            let scale = Complex::new(sigma * renormalise, 0.0);
+            right_factor.row_mut(row).iter_mut().for_each(|x| *x *= scale);
+        }
+        self.sites[site] = [
+            u.view((0, 0), (left, keep)).into_owned(),
+            u.view((left, 0), (left, keep)).into_owned(),
+        ];
+        self.sites[site + 1] = [
+            right_factor.columns(0, right).into_owned(),
+            right_factor.columns(right, right).into_owned(),
+        ];
+        self.center = site + 1;
+    }
+
+    /// Number of singular values to keep, spending the remaining error budget on the
+    /// smallest ones and then capping at the bond dimension
+    fn truncate(&mut self, weights: &[f64], total: f64) -> usize {
+        if total <= 0.0 {
+            return 1;
+        }
+        let allowance = (self.error_budget - self.truncation_error).max(0.0) + 1e-14;
+        let mut keep = weights.len();
+        let mut discarded = 0.0;
+        while keep > 1 && discarded + weights[keep - 1] / total <= allowance {
+            discarded += weights[keep - 1] / total;
+            keep -= 1;
+        }
+        while keep > self.max_bond {
+            discarded += weights[keep - 1] / total;
+            keep -= 1;
+        }
+        self.truncation_error += discarded;
+        keep
+    }
+
+    /// Shift the orthogonality centre to `target` with QR sweeps
+    fn move_center(&mut self, target: usize) {
+        while self.center < target {
+            let site = self.center;
+            let (left, bond) = (self.sites[site][0].nrows(), self.sites[site][0].ncols());
+            let mut stacked = Matrix::zeros(2 * left, bond);
+            stacked.view_mut((0, 0), (left, bond)).copy_from(&self.sites[site][0]);
+            stacked.view_mut((left, 0), (left, bond)).copy_from(&self.sites[site][1]);
+            let qr = stacked.qr();
+            let (q, r) = (qr.q(), qr.r());
+            let k = q.ncols();
+            self.sites[site] = [q.view((0, 0), (left, k)).into_owned(), q.view((left, 0), (left, k)).into_owned()];
+            let next = &self.sites[site + 1];
+            self.sites[site + 1] = [&r * &next[0], &r * &next[1]];
+            self.center += 1;
+        }
+        while self.center > target {
+            let site = self.center;
+            let (bond, right) = (self.sites[site][0].nrows(), self.sites[site][0].ncols());
+            let mut stacked = Matrix::zeros(bond, 2 * right);
+            stacked.view_mut((0, 0), (bond, right)).copy_from(&self.sites[site][0]);
+            stacked.view_mut((0, right), (bond, right)).copy_from(&self.sites[site][1]);
+            // LQ via the QR decomposition of the adjoint
+            let qr = stacked.adjoint().qr();
+            let (q, l) = (qr.q().adjoint(), qr.r().adjoint());
+            let k = q.nrows();
+            self.sites[site] = [q.view((0, 0), (k, right)).into_owned(), q.view((0, right), (k, right)).into_owned()];
+            let previous = &self.sites[site - 1];
+            self.sites[site - 1] = [&previous[0] * &l, &previous[1] * &l];
+            self.center -= 1;
+        }
+    }
+}
+
+fn swap_matrix() -> Matrix {
+    let mut swap = Matrix::zeros(4, 4);
+    for (row, col) in [(0, 0), (1, 2), (2, 1), (3, 3)] {
+        swap[(row, col)] = Complex::new(1.0, 0.0);
+    }
+    swap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quantum_simulation::{Circuit, QuantumSimulator};
+
+    #[test]
+    fn test_mps_matches_state_vector() {
+        // Entangling circuit with non-adjacent gates that need SWAP routing
+        let simulator = QuantumSimulator::ideal(5);
+        let mut circuit = Circuit::new(5);
+        for qubit in 0..5 {
+            circuit.ry(qubit, 0.3 + 0.4 * qubit as f64).rz(qubit, 0.2 * qubit as f64);
+        }
+        circuit.cx(0, 3).cx(4, 1).h(2).cx(2, 0).rx(3, 1.1).cx(1, 4).cx(3, 2);
+
+        let mut mps = MatrixProductState::new(5, 32, 0.0);
+        for instruction in &circuit.instructions {
+            match instruction.control {
+                Some(control) => mps.cx(control, instruction.target),
+                None => mps.apply_single(instruction.target, &simulator.single_qubit_matrix(&instruction.operation)),
+            }
+        }
+        let expected = simulator.statevector(&circuit);
+        let actual = mps.statevector();
+        let overlap: Complex<f64> = expected.iter().zip(&actual).map(|(e, a)| e.conj() * a).sum();
+        assert!((overlap.norm_sqr() - 1.0).abs() < 1e-9);
+        assert!(mps.truncation_error < 1e-12);
+
+        let mut rng = rand::thread_rng();
+        let outcome = mps.measure(1, &mut rng);
+        let norm: f64 = mps.statevector().iter().map(|a| a.norm_sqr()).sum();
+        assert!((norm - 1.0).abs() < 1e-9);
+        assert!(mps.statevector().iter().enumerate().all(|(i, a)| (i >> 1 & 1 == 1) == outcome || a.norm_sqr() < 1e-20));
+    }
+
+    #[test]
+    fn test_bond_dimension_truncation() {
+        // A 60-qubit GHZ chain needs bond dimension two everywhere
+        let mut ghz = MatrixProductState::new(60, 8, 1e-10);
+        ghz.apply_single(0, &QuantumSimulator::ideal(1).single_qubit_matrix(&crate::quantum::qasm::QuantumOperation::H));
+        for qubit in 1..60 {
+            ghz.cx(qubit - 1, qubit);
+        }
+        assert!(ghz.bond_dimensions().iter().all(|&d| d == 2));
+        let mut rng = rand::thread_rng();
+        let first = ghz.measure(30, &mut rng);
+        assert!((0..60).all(|q| ghz.measure(q, &mut rng) == first));
+
+        // Capping a Bell pair at bond one discards half the weight and exceeds the budget
+        let mut capped = MatrixProductState::new(2, 1, 1e-3);
+        capped.apply_single(0, &QuantumSimulator::ideal(1).single_qubit_matrix(&crate::quantum::qasm::QuantumOperation::H));
+        capped.cx(0, 1);
+        assert!((capped.truncation_error - 0.5).abs() < 1e-12);
+        assert!(!capped.within_budget());
+    }
+
+    #[test]
+    fn test_large_circuits_select_mps() {
+        use crate::quantum_simulation::Backend;
+
+        // RY(π/2) makes the GHZ preparation non-Clifford, so 80 qubits need the MPS backend
+        let qubits = 80;
+        let mut circuit = Circuit::new(qubits);
+        circuit.ry(0, std::f64::consts::FRAC_PI_2);
+        for qubit in 1..qubits {
+            circuit.cx(qubit - 1, qubit);
+        }
+        circuit.measure_all();
+        let simulator = QuantumSimulator::ideal(qubits);
+        assert_eq!(simulator.select_backend(&circuit), Backend::MatrixProductState);
+        let counts = simulator.run(&circuit, 10).unwrap();
+        assert_eq!(counts.get(&"0".repeat(qubits)) + counts.get(&"1".repeat(qubits)), 10);
+
+        // Gates the chain cannot apply fail the run, before or after a measurement
+        use crate::quantum::qasm::QuantumOperation;
+        let mut simulator = QuantumSimulator::ideal(qubits);
+        simulator.backend = Some(Backend::MatrixProductState);
+        let mut uncontrolled = Circuit::new(qubits);
+        uncontrolled.push(QuantumOperation::CX, 1, None).measure_all();
+        assert!(simulator.run(&uncontrolled, 3).is_err());
+        let mut late = Circuit::new(qubits);
+        late.ry(0, 0.3).measure(0).push(QuantumOperation::CX, 2, None);
+        assert!(simulator.run(&late, 3).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+// Pauli-string observables
+#![allow(dead_code)]
+
+use nalgebra::{Complex, DMatrix};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+/// Tensor product of single-qubit Paulis; qubits not listed carry the identity
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct PauliString {
+    /// Non-identity factors sorted by qubit
+    pub factors: Vec<(usize, Pauli)>,
+}
+
+impl PauliString {
+    pub fn new(factors: &[(usize, Pauli)]) -> Result<Self, String> {
+        let mut sorted: Vec<(usize, Pauli)> = factors.iter().copied().filter(|(_, p)| *p != Pauli::I).collect();
+        sorted.sort();
+        if sorted.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(format!("Pauli string {:?} repeats a qubit", factors));
+        }
+        Ok(PauliString { factors: sorted })
+    }
+
+    /// Parse a label such as `"XIZ"`, where character `k` acts on qubit `k`
+    pub fn from_label(label: &str) -> Result<Self, String> {
+        let factors = label.chars()
+            .enumerate()
+            .map(|(qubit, c)| match c.to_ascii_uppercase() {
+                'I' => Ok((qubit, Pauli::I)),
+                'X' => Ok((qubit, Pauli::X)),
+                'Y' => Ok((qubit, Pauli::Y)),
+                'Z' => Ok((qubit, Pauli::Z)),
+                other => Err(format!("Unknown Pauli '{}' in '{}'", other, label)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(&factors)
+    }
+
+    pub fn identity() -> Self {
+        PauliString::default()
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.factors.is_empty()
+    }
+
+    /// Pauli acting on `qubit`
+    pub fn on(&self, qubit: usize) -> Pauli {
+        self.factors.iter().find(|(q, _)| *q == qubit).map_or(Pauli::I, |(_, p)| *p)
+    }
+
+    /// Highest qubit index plus one
+    pub fn span(&self) -> usize {
+        self.factors.last().map_or(0, |(q, _)| q + 1)
+    }
+
+    /// Bits flipped by the string (X and Y) and bits picking up a sign (Y and Z)
+    fn masks(&self) -> (usize, usize, u32) {
+        let (mut flip, mut sign, mut ys) = (0usize, 0usize, 0u32);
+        for &(qubit, pauli) in &self.factors {
+            match pauli {
+                Pauli::X => flip |= 1 << qubit,
+                Pauli::Y => {
+                    flip |= 1 << qubit;
+                    sign |= 1 << qubit;
+                    ys += 1;
+                }
+                Pauli::Z => sign |= 1 << qubit,
+                Pauli::I => {}
+            }
+        }
+        (flip, sign, ys)
+    }
+
+    /// ⟨ψ|P|ψ⟩ for a state vector whose qubit `q` is bit `q` of the index
+    pub fn expectation(&self, state: &[Complex<f64>]) -> Result<f64, String> {
+        check_register(self.span(), state.len())?;
+        Ok(self.expectation_unchecked(state))
+    }
+
+    fn expectation_unchecked(&self, state: &[Complex<f64>]) -> f64 {
+        let (flip, sign, ys) = self.masks();
+        // Y|b⟩ = i(−1)^b |1−b⟩, so the string contributes i^{#Y} overall
+        let global = Complex::new(0.0, 1.0).powu(ys);
+        let total: Complex<f64> = state.iter()
+            .enumerate()
+            .map(|(i, amplitude)| {
+                let parity = if (i & sign).count_ones() % 2 == 1 { -1.0 } else { 1.0 };
+                state[i ^ flip].conj() * amplitude * parity
+            })
+            .sum();
+        (total * global).re
+    }
+
+    /// Tr(ρP) for a density matrix on the same qubit ordering
+    pub fn expectation_density(&self, rho: &DMatrix<Complex<f64>>) -> Result<f64, String> {
+        check_register(self.span(), rho.nrows())?;
+        Ok(self.expectation_density_unchecked(rho))
+    }
+
+    fn expectation_density_unchecked(&self, rho: &DMatrix<Complex<f64>>) -> f64 {
+        let (flip, sign, ys) = self.masks();
+        let global = Complex::new(0.0, 1.0).powu(ys);
+        let total: Complex<f64> = (0..rho.nrows())
+            .map(|i| {
+                let parity = if (i & sign).count_ones() % 2 == 1 { -1.0 } else { 1.0 };
+                rho[(i, i ^ flip)] * parity
+            })
+            .sum();
+        (total * global).re
+    }
+
+    /// Whether the two strings agree on every qubit where both act, so a single
+    /// product-basis measurement serves both
+    pub fn qubit_wise_commutes(&self, other: &PauliString) -> bool {
+        self.factors.iter().all(|&(qubit, pauli)| {
+            let theirs = other.on(qubit);
+            theirs == Pauli::I || theirs == pauli
+        })
+    }
+
+    /// Dense matrix on `qubits` qubits
+    pub fn matrix(&self, qubits: usize) -> Result<DMatrix<Complex<f64>>, String> {
+        check_width(self.span(), qubits)?;
+        Ok(self.matrix_unchecked(qubits))
+    }
+
+    fn matrix_unchecked(&self, qubits: usize) -> DMatrix<Complex<f64>> {
+        let (flip, sign, ys) = self.masks();
+        let global = Complex::new(0.0, 1.0).powu(ys);
+        let dim = 1usize << qubits;
+        let mut matrix = DMatrix::zeros(dim, dim);
+        for i in 0..dim {
+            let parity = if (i & sign).count_ones() % 2 == 1 { -1.0 } else { 1.0 };
+            matrix[(i ^ flip, i)] = global * parity;
+        }
+        matrix
+    }
+}
+
+/// Terms measured together in one product basis
+#[derive(Debug, Clone)]
+pub struct MeasurementGroup {
+    /// Pauli measured on each qubit; its factors cover every term of the group
+    pub basis: PauliString,
+    /// Indices into `Observable::terms`
+    pub terms: Vec<usize>,
+}
+
+/// Shot-based estimate of an expectation value
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    pub value: f64,
+    /// Variance of the estimator, i.e. the squared standard error
+    pub variance: f64,
+    pub standard_error: f64,
+    /// Shots spent across all groups
+    pub shots: usize,
+    pub groups: usize,
+}
+
+/// Weighted sum of Pauli strings, e.g. a qubit Hamiltonian
+#[derive(Debug, Clone, Default)]
+pub struct Observable {
+    pub terms: Vec<(f64, PauliString)>,
+}
+
+impl Observable {
+    pub fn new() -> Self {
+        Observable { terms: Vec::new() }
+    }
+
+    /// Add `coefficient · P`, merging with an existing identical string
+    pub fn add_term(&mut self, coefficient: f64, pauli: PauliString) -> &mut Self {
+        match self.terms.iter_mut().find(|(_, p)| *p == pauli) {
+            Some((c, _)) => *c += coefficient,
+            None => self.terms.push((coefficient, pauli)),
+        }
+        self
+    }
+
+    /// Parse terms such as `"-1.05 II + 0.39 ZI - 0.18 XX"`; operators need spaces around them
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut observable = Observable::new();
+        let mut tokens = source.split_whitespace();
+        let mut sign = 1.0;
+        while let Some(token) = tokens.next() {
+            match token {
+                "+" => sign = 1.0,
+                "-" => sign = -1.0,
+                coefficient => {
+                    let value: f64 = coefficient.parse()
+                        .map_err(|_| format!("Invalid coefficient '{}'", coefficient))?;
+                    let label = tokens.next()
+                        .ok_or_else(|| format!("Missing Pauli label after '{}'", coefficient))?;
+                    observable.add_term(sign * value, PauliString::from_label(label)?);
+                    sign = 1.0;
+                }
+            }
+        }
+        Ok(observable)
+    }
+
+    /// Number of qubits the observable acts on
+    pub fn span(&self) -> usize {
+        self.terms.iter().map(|(_, p)| p.span()).max().unwrap_or(0)
+    }
+
+    /// Exact ⟨ψ|H|ψ⟩
+    pub fn expectation(&self, state: &[Complex<f64>]) -> Result<f64, String> {
+        check_register(self.span(), state.len())?;
+        Ok(self.terms.iter().map(|(c, p)| c * p.expectation_unchecked(state)).sum())
+    }
+
+    /// Exact Tr(ρH)
+    pub fn expectation_density(&self, rho: &DMatrix<Complex<f64>>) -> Result<f64, String> {
+        check_register(self.span(), rho.nrows())?;
+        Ok(self.terms.iter().map(|(c, p)| c * p.expectation_density_unchecked(rho)).sum())
+    }
+
+    /// H|ψ⟩ without building the dense matrix
+    pub fn apply(&self, state: &[Complex<f64>]) -> Result<Vec<Complex<f64>>, String> {
+        check_register(self.span(), state.len())?;
+        let mut result = vec![Complex::new(0.0, 0.0); state.len()];
+        for (coefficient, pauli) in &self.terms {
+            let (flip, sign, ys) = pauli.masks();
+            let global = Complex::new(0.0, 1.0).powu(ys) * *coefficient;
+            for (i, amplitude) in state.iter().enumerate() {
+                let parity = if (i & sign).count_ones() % 2 == 1 { -1.0 } else { 1.0 };
+                result[i ^ flip] += global * amplitude * parity;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Exact ⟨H²⟩ − ⟨H⟩², the spread of single-shot energies
+    pub fn variance(&self, state: &[Complex<f64>]) -> Result<f64, String> {
+        let mean = self.expectation(state)?;
+        let second: f64 = self.apply(state)?.iter().map(|a| a.norm_sqr()).sum();
+        Ok((second - mean * mean).max(0.0))
+    }
+
+    /// Greedily partition the non-identity terms into qubit-wise commuting groups
+    pub fn group_commuting(&self) -> Vec<MeasurementGroup> {
+        let mut groups: Vec<MeasurementGroup> = Vec::new();
+        for (index, (_, pauli)) in self.terms.iter().enumerate() {
+            if pauli.is_identity() {
+                continue;
+            }
+            match groups.iter_mut().find(|g| pauli.qubit_wise_commutes(&g.basis)) {
+                Some(group) => {
+                    let mut factors = group.basis.factors.clone();
+                    factors.extend(pauli.factors.iter().filter(|(q, _)| group.basis.on(*q) == Pauli::I));
+                    group.basis = PauliString::new(&factors).expect("commuting factors never clash");
+                    group.terms.push(index);
+                }
+                None => groups.push(MeasurementGroup { basis: pauli.clone(), terms: vec![index] }),
+            }
+        }
+        groups
+    }
+
+    /// Estimate ⟨ψ|H|ψ⟩ from `shots` computational-basis samples per commuting group
+    pub fn estimate<R: Rng>(&self, state: &[Complex<f64>], shots: usize, rng: &mut R) -> Result<Estimate, String> {
+        check_register(self.span(), state.len())?;
+        let groups = self.group_commuting();
+        let shots = shots.max(1);
+        let mut value: f64 = self.terms.iter().filter(|(_, p)| p.is_identity()).map(|(c, _)| c).sum();
+        let mut variance = 0.0;
+        for group in &groups {
+            let probabilities = rotated_probabilities(state, &group.basis);
+            let cumulative: Vec<f64> = probabilities.iter()
+                .scan(0.0, |total, p| {
+                    *total += p;
+                    Some(*total)
+                })
+                .collect();
+            let norm = cumulative.last().copied().unwrap_or(0.0);
+            let masks: Vec<(f64, usize)> = group.terms.iter()
+                .map(|&t| (self.terms[t].0, self.terms[t].1.factors.iter().fold(0usize, |m, (q, _)| m | 1 << q)))
+                .collect();
+
+            let (mut sum, mut sum_sq) = (0.0, 0.0);
+            for _ in 0..shots {
+                let draw = rng.gen::<f64>() * norm;
+                let outcome = cumulative.partition_point(|&c| c < draw).min(cumulative.len() - 1);
+                let sample: f64 = masks.iter()
+                    .map(|&(c, mask)| if (outcome & mask).count_ones() % 2 == 1 { -c } else { c })
+                    .sum();
+                sum += sample;
+                sum_sq += sample * sample;
+            }
+            let mean = sum / shots as f64;
+            value += mean;
+            if shots > 1 {
+                let sample_variance = (sum_sq - shots as f64 * mean * mean) / (shots - 1) as f64;
+                variance += sample_variance.max(0.0) / shots as f64;
+            }
+        }
+        Ok(Estimate {
+            value,
+            variance,
+            standard_error: variance.sqrt(),
+            shots: shots * groups.len(),
+            groups: groups.len(),
+        })
+    }
+
+    pub fn matrix(&self, qubits: usize) -> Result<DMatrix<Complex<f64>>, String> {
+        check_width(self.span(), qubits)?;
+        let dim = 1usize << qubits;
+        Ok(self.terms.iter().fold(DMatrix::zeros(dim, dim), |acc, (c, p)| acc + p.matrix_unchecked(qubits) * Complex::new(*c, 0.0)))
+    }
+
+    /// Smallest eigenvalue by exact diagonalisation
+    pub fn ground_energy(&self, qubits: usize) -> Result<f64, String> {
+        Ok(self.matrix(qubits)?.symmetric_eigenvalues().iter().cloned().fold(f64::INFINITY, f64::min))
+    }
+}
+
+/// Reject registers that are not 2ⁿ amplitudes or too narrow for `span` qubits
+fn check_register(span: usize, dimension: usize) -> Result<(), String> {
+    if !dimension.is_power_of_two() {
+        return Err(format!("A register of {} amplitudes is not a power of two", dimension));
+    }
+    check_width(span, dimension.trailing_zeros() as usize)
+}
+
+/// Reject operators acting on more than `qubits` qubits
+fn check_width(span: usize, qubits: usize) -> Result<(), String> {
+    if span > qubits {
+        return Err(format!("Operator acts on {} qubits but the register has {}", span, qubits));
+    }
+    Ok(())
+}
+
+/// Outcome probabilities after rotating each qubit of `basis` into the Z basis
+///
+/// `basis` must fit the register; callers check with `check_register`.
+fn rotated_probabilities(state: &[Complex<f64>], basis: &PauliString) -> Vec<f64> {
+    let h = Complex::new(1.0 / 2f64.sqrt(), 0.0);
+    let mut rotated = state.to_vec();
+    for &(qubit, pauli) in &basis.factors {
+        // X is read out after H, Y after S† then H
+        let phase = match pauli {
+            Pauli::X => Complex::new(1.0, 0.0),
+            Pauli::Y => Complex::new(0.0, -1.0),
+            _ => continue,
+        };
+        let mask = 1usize << qubit;
+        for i in (0..rotated.len()).filter(|i| i & mask == 0) {
+            let (a0, a1) = (rotated[i], rotated[i | mask] * phase);
+            rotated[i] = h * (a0 + a1);
+            rotated[i | mask] = h * (a0 - a1);
+        }
+    }
+    rotated.iter().map(|a| a.norm_sqr()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauli_expectations() {
+        let h = 1.0 / 2f64.sqrt();
+        let zero = Complex::new(0.0, 0.0);
+        // |+⟩ on qubit 0, |1⟩ on qubit 1: amplitudes at indices 2 and 3
+        let state = vec![zero, zero, Complex::new(h, 0.0), Complex::new(h, 0.0)];
+        assert!((PauliString::from_label("XI").unwrap().expectation(&state).unwrap() - 1.0).abs() < 1e-12);
+        assert!((PauliString::from_label("IZ").unwrap().expectation(&state).unwrap() + 1.0).abs() < 1e-12);
+        assert!(PauliString::from_label("ZI").unwrap().expectation(&state).unwrap().abs() < 1e-12);
+
+        // (|0⟩ + i|1⟩)/√2 is the +1 eigenstate of Y
+        let plus_i = vec![Complex::new(h, 0.0), Complex::new(0.0, h)];
+        assert!((PauliString::from_label("Y").unwrap().expectation(&plus_i).unwrap() - 1.0).abs() < 1e-12);
+
+        let observable = Observable::parse("0.5 XI - 2 IZ + 1 II").unwrap();
+        assert!((observable.expectation(&state).unwrap() - 3.5).abs() < 1e-12);
+        let matrix = observable.matrix(2).unwrap();
+        let vector = nalgebra::DVector::from_vec(state.clone());
+        let direct = (vector.adjoint() * &matrix * &vector)[(0, 0)].re;
+        assert!((direct - 3.5).abs() < 1e-12);
+        assert!(Observable::parse("0.5 XQ").is_err());
+
+        // Operators wider than the register are rejected instead of indexing past it
+        let wide = Observable::parse("1 XXX").unwrap();
+        assert!(wide.expectation(&state).is_err());
+        assert!(wide.apply(&state).is_err());
+        assert!(wide.estimate(&state, 10, &mut rand::thread_rng()).is_err());
+        assert!(PauliString::from_label("IIZ").unwrap().expectation(&state).is_err());
+        assert!(wide.matrix(2).is_err());
+        assert!(wide.ground_energy(2).is_err());
+        assert!(PauliString::from_label("IIZ").unwrap().matrix(2).is_err());
+        assert!(observable.expectation(&state[..3]).is_err());
+
+        // Density-matrix expectation agrees with the pure-state one
+        let vector = nalgebra::DVector::from_vec(state.clone());
+        let rho = &vector * vector.adjoint();
+        assert!((observable.expectation_density(&rho).unwrap() - 3.5).abs() < 1e-12);
+        assert!((PauliString::from_label("YY").unwrap().expectation_density(&rho).unwrap()
+            - PauliString::from_label("YY").unwrap().expectation(&state).unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_grouping_and_shot_estimates() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let observable = Observable::parse("0.5 ZZ + 0.3 ZI + 0.2 XX - 0.4 YY + 0.1 XI + 1.0 II").unwrap();
+        let groups = observable.group_commuting();
+        assert_eq!(groups.len(), 3);
+        for group in &groups {
+            for &t in &group.terms {
+                assert!(observable.terms[t].1.qubit_wise_commutes(&group.basis));
+            }
+        }
+
+        // (|00⟩ + i|11⟩)/√2 tilted by a phase so every term contributes
+        let h = 1.0 / 2f64.sqrt();
+        let zero = Complex::new(0.0, 0.0);
+        let state = vec![Complex::new(h * 0.8, 0.0), Complex::new(h * 0.6, 0.0), zero, Complex::new(0.0, h)];
+        let exact = observable.expectation(&state).unwrap();
+        let variance = observable.variance(&state).unwrap();
+        let rho = {
+            let v = nalgebra::DVector::from_vec(state.clone());
+            &v * v.adjoint()
+        };
+        let matrix = observable.matrix(2).unwrap();
+        let squared = (&rho * &matrix * &matrix).trace().re;
+        assert!((variance - (squared - exact * exact)).abs() < 1e-12);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let estimate = observable.estimate(&state, 20000, &mut rng).unwrap();
+        assert_eq!(estimate.groups, 3);
+        assert_eq!(estimate.shots, 60000);
+        assert!(estimate.standard_error > 0.0 && estimate.standard_error < 0.01);
+        assert!((estimate.value - exact).abs() < 5.0 * estimate.standard_error);
+    }
+}
@@ -0,0 +1,110 @@
+// Measurement records from repeated circuit executions
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+/// Histogram of classical-register outcomes over a number of shots
+///
+/// Keys are bitstrings with classical bit 0 rightmost, as in OpenQASM output.
+#[derive(Debug, Clone, Default)]
+pub struct Counts {
+    pub histogram: BTreeMap<String, usize>,
+    pub shots: usize,
+    pub clbits: usize,
+}
+
+impl Counts {
+    pub fn new(clbits: usize) -> Self {
+        Counts {
+            histogram: BTreeMap::new(),
+            shots: 0,
+            clbits,
+        }
+    }
+
+    /// Add one shot's classical register, indexed by classical bit
+    pub fn record(&mut self, register: &[bool]) {
+        let key: String = register.iter().rev().map(|&bit| if bit { '1' } else { '0' }).collect();
+        *self.histogram.entry(key).or_insert(0) += 1;
+        self.shots += 1;
+    }
+
+    pub fn get(&self, bitstring: &str) -> usize {
+        self.histogram.get(bitstring).copied().unwrap_or(0)
+    }
+
+    /// Relative frequency of an outcome
+    pub fn probability(&self, bitstring: &str) -> f64 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        self.get(bitstring) as f64 / self.shots as f64
+    }
+
+    pub fn most_frequent(&self) -> Option<(&str, usize)> {
+        self.histogram.iter()
+            .max_by_key(|(_, &count)| count)
+            .map(|(key, &count)| (key.as_str(), count))
+    }
+
+    /// Counts restricted to the listed classical bits, the first listed becoming bit 0
+    pub fn marginal(&self, clbits: &[usize]) -> Counts {
+        let mut marginal = Counts::new(clbits.len());
+        for (key, &count) in &self.histogram {
+            let bits: Vec<char> = key.chars().rev().collect();
+            let reduced: String = clbits.iter().rev().map(|&c| bits.get(c).copied().unwrap_or('0')).collect();
+            *marginal.histogram.entry(reduced).or_insert(0) += count;
+        }
+        marginal.shots = self.shots;
+        marginal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::quantum_simulation::{Circuit, QuantumSimulator};
+
+    #[test]
+    fn test_bell_counts_and_partial_measurement() {
+        let simulator = QuantumSimulator::ideal(2);
+        let mut bell = Circuit::new(2);
+        bell.h(0).cx(0, 1);
+
+        let mut state = simulator.statevector(&bell);
+        let outcome = simulator.measure_qubit(&mut state, 0);
+        let survivor = if outcome { 3 } else { 0 };
+        assert!((state[survivor].norm_sqr() - 1.0).abs() < 1e-12);
+
+        bell.measure_all();
+        let counts = simulator.run(&bell, 4000).unwrap();
+        assert_eq!(counts.shots, 4000);
+        assert_eq!(counts.get("00") + counts.get("11"), 4000);
+        assert!((counts.probability("11") - 0.5).abs() < 0.05);
+        assert_eq!(counts.marginal(&[1]).get("1"), counts.get("11"));
+
+        // Circuits must match the simulator's width and stay inside their register
+        assert!(QuantumSimulator::ideal(3).run(&bell, 10).is_err());
+        let mut stray = Circuit::new(2);
+        stray.h(2);
+        assert!(simulator.run(&stray, 10).is_err());
+    }
+
+    #[test]
+    fn test_mid_circuit_measurement_and_readout_error() {
+        // Measure qubit 0, copy it onto qubit 1, then reset qubit 0 with X and measure again
+        let simulator = QuantumSimulator::ideal(2);
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).measure_into(0, 0).cx(0, 1).measure_into(1, 1).x(0).measure_into(0, 2);
+        assert!(!circuit.has_terminal_measurements());
+        let counts = simulator.run(&circuit, 500).unwrap();
+        assert_eq!(counts.clbits, 3);
+        assert_eq!(counts.get("011") + counts.get("100"), 500);
+
+        let mut noisy = QuantumSimulator::ideal(1);
+        noisy.readout_error = (0.2, 0.0);
+        let mut idle = Circuit::new(1);
+        idle.measure(0);
+        let counts = noisy.run(&idle, 5000).unwrap();
+        assert!((counts.probability("1") - 0.2).abs() < 0.03);
+    }
+}
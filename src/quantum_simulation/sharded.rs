@@ -0,0 +1,260 @@
+// Sharded state vectors: amplitudes split across worker threads by the top qubits
+#![allow(dead_code)]
+
+use crate::quantum::qasm::QuantumOperation;
+use crate::quantum_simulation::{Circuit, QuantumSimulator};
+use nalgebra::{Complex, DMatrix};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+type Amplitudes = Vec<Complex<f64>>;
+
+/// Outcome of a sharded run
+///
+/// The state stays split across the nodes' shards; `gather` assembles the full
+/// state vector when it fits on one machine.
+#[derive(Debug, Clone)]
+pub struct ShardedResult {
+    /// Amplitudes of each node by rank; entry `i` of shard `r` sits at physical
+    /// index `r << local | i`
+    pub shards: Vec<Vec<Complex<f64>>>,
+    /// Physical bit position of each logical qubit after the run
+    pub layout: Vec<usize>,
+    /// Half-shard exchanges performed by every node
+    pub exchanges: usize,
+    /// Amplitudes sent over channels by all nodes together
+    pub amplitudes_sent: usize,
+}
+
+impl ShardedResult {
+    /// Logical basis index, qubit `q` as bit `q`, of amplitude `i` in shard `rank`
+    pub fn logical_index(&self, rank: usize, i: usize) -> usize {
+        let local = self.shards.first().map_or(0, |s| s.len().trailing_zeros() as usize);
+        let physical = rank << local | i;
+        self.layout.iter().enumerate().fold(0usize, |index, (q, &p)| index | (physical >> p & 1) << q)
+    }
+
+    /// Most probable basis state as `(logical index, probability)`, found shard by shard
+    pub fn peak(&self) -> Option<(usize, f64)> {
+        self.shards.iter()
+            .enumerate()
+            .filter_map(|(rank, shard)| {
+                shard.iter()
+                    .map(|a| a.norm_sqr())
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(i, p)| (rank, i, p))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(rank, i, p)| (self.logical_index(rank, i), p))
+    }
+
+    /// Full state vector with qubit `q` as bit `q` of the index
+    ///
+    /// Allocates all 2ⁿ amplitudes on the calling thread.
+    pub fn gather(&self) -> Vec<Complex<f64>> {
+        let mut statevector = vec![Complex::new(0.0, 0.0); self.shards.iter().map(Vec::len).sum()];
+        for (rank, shard) in self.shards.iter().enumerate() {
+            for (i, &amplitude) in shard.iter().enumerate() {
+                statevector[self.logical_index(rank, i)] = amplitude;
+            }
+        }
+        statevector
+    }
+}
+
+/// One step every node executes in lockstep, on physical bit positions
+///
+/// Positions below `local` index a node's shard; position `local + j` is bit `j`
+/// of the node's rank.
+#[derive(Debug, Clone)]
+enum Step {
+    /// Gate whose qubits all live inside each shard
+    Local { operation: QuantumOperation, target: usize, control: Option<usize> },
+    /// Diagonal gate on a global qubit: a phase per node
+    Phase { bit: usize, phases: [Complex<f64>; 2] },
+    /// CX controlled by a global qubit: X on nodes whose rank bit is set
+    RankControlled { bit: usize, target: usize },
+    /// Swap global bit `bit` with local position `local` by trading half a shard
+    Exchange { bit: usize, local: usize },
+}
+
+/// Translate a circuit into lockstep steps, moving global targets into shards
+/// as needed, and return the final logical-to-physical layout
+fn plan(circuit: &Circuit, local: usize, gates: &QuantumSimulator) -> Result<(Vec<Step>, Vec<usize>), String> {
+    let mut layout: Vec<usize> = (0..circuit.qubits).collect();
+    let mut steps = Vec::new();
+    for instruction in &circuit.instructions {
+        let in_range = instruction.target < circuit.qubits && instruction.control.is_none_or(|c| c < circuit.qubits && c != instruction.target);
+        if !in_range {
+            return Err(format!("{:?} addresses qubits outside the {}-qubit register", instruction.operation, circuit.qubits));
+        }
+        if instruction.control.is_some() && !matches!(instruction.operation, QuantumOperation::CX) {
+            return Err(format!("Only CX can be controlled on a sharded state, not {:?}", instruction.operation));
+        }
+        let control = instruction.control.map(|c| layout[c]);
+        let mut target = layout[instruction.target];
+        match &instruction.operation {
+            QuantumOperation::Measure => return Err("Measurements are not supported on a sharded state; measure the gathered state".to_string()),
+            QuantumOperation::Custom(name) => return Err(format!("Custom operation '{}' cannot be sharded", name)),
+            QuantumOperation::CX if control.is_none() => return Err("CX needs a control qubit".to_string()),
+            QuantumOperation::CX => {}
+            gate => {
+                let matrix: DMatrix<Complex<f64>> = gates.single_qubit_matrix(gate);
+                let diagonal = matrix[(0, 1)].norm_sqr() == 0.0 && matrix[(1, 0)].norm_sqr() == 0.0;
+                if target >= local && diagonal {
+                    steps.push(Step::Phase { bit: target - local, phases: [matrix[(0, 0)], matrix[(1, 1)]] });
+                    continue;
+                }
+            }
+        }
+        if target >= local {
+            // Bring the target into the shards through a free local position
+            let free = (0..local).rev().find(|&p| Some(p) != control).ok_or("No free local qubit for an exchange")?;
+            steps.push(Step::Exchange { bit: target - local, local: free });
+            let displaced = layout.iter().position(|&p| p == free).expect("layout is a permutation");
+            layout.swap(displaced, instruction.target);
+            target = free;
+        }
+        match control {
+            Some(control) if control >= local => steps.push(Step::RankControlled { bit: control - local, target }),
+            _ => steps.push(Step::Local { operation: instruction.operation.clone(), target, control }),
+        }
+    }
+    Ok((steps, layout))
+}
+
+/// Run every step on one node's shard; returns the shard and the amplitudes sent
+fn execute(
+    rank: usize,
+    mut shard: Amplitudes,
+    steps: &[Step],
+    gates: &QuantumSimulator,
+    senders: Vec<Sender<Amplitudes>>,
+    receivers: Vec<Receiver<Amplitudes>>,
+) -> Result<(Amplitudes, usize), String> {
+    let mut sent = 0;
+    for step in steps {
+        match step {
+            Step::Local { operation, target, control } => gates.apply_gate(&mut shard, operation, *target, *control),
+            Step::Phase { bit, phases } => {
+                let phase = phases[rank >> bit & 1];
+                shard.iter_mut().for_each(|a| *a *= phase);
+            }
+            Step::RankControlled { bit, target } => {
+                if rank >> bit & 1 == 1 {
+                    gates.apply_gate(&mut shard, &QuantumOperation::X, *target, None);
+                }
+            }
+            Step::Exchange { bit, local } => {
+                // Amplitudes whose local bit differs from our rank bit belong to the partner
+                let own = rank >> bit & 1;
+                let partner = rank ^ (1 << bit);
+                let outgoing: Vec<usize> = (0..shard.len()).filter(|i| i >> local & 1 != own).collect();
+                let half: Amplitudes = outgoing.iter().map(|&i| shard[i]).collect();
+                sent += half.len();
+                senders[partner].send(half).map_err(|_| format!("Node {} hung up", partner))?;
+                let incoming = receivers[partner].recv().map_err(|_| format!("Node {} hung up", partner))?;
+                for (&i, amplitude) in outgoing.iter().zip(incoming) {
+                    shard[i] = amplitude;
+                }
+            }
+        }
+    }
+    Ok((shard, sent))
+}
+
+/// Simulate `circuit` from |0…0⟩ on `nodes` threads, each holding one shard
+pub fn run_sharded(circuit: &Circuit, nodes: usize) -> Result<ShardedResult, String> {
+    if !nodes.is_power_of_two() {
+        return Err(format!("Node count {} is not a power of two", nodes));
+    }
+    let global = nodes.trailing_zeros() as usize;
+    if circuit.qubits < global + 2 {
+        return Err(format!("{} qubits cannot be sharded across {} nodes", circuit.qubits, nodes));
+    }
+    let local = circuit.qubits - global;
+    let gates = QuantumSimulator::ideal(local);
+    let (steps, layout) = plan(circuit, local, &gates)?;
+
+    // One channel per ordered pair of nodes keeps every exchange in FIFO order
+    let mut senders: Vec<Vec<Sender<Amplitudes>>> = (0..nodes).map(|_| Vec::new()).collect();
+    let mut receivers: Vec<Vec<Receiver<Amplitudes>>> = (0..nodes).map(|_| Vec::new()).collect();
+    for inbox in receivers.iter_mut() {
+        for outbox in senders.iter_mut() {
+            let (sender, receiver) = channel();
+            outbox.push(sender);
+            inbox.push(receiver);
+        }
+    }
+
+    let outcomes: Vec<Result<(Amplitudes, usize), String>> = thread::scope(|scope| {
+        let handles: Vec<_> = senders.into_iter()
+            .zip(receivers)
+            .enumerate()
+            .map(|(rank, (outbox, inbox))| {
+                let (steps, gates) = (&steps, &gates);
+                scope.spawn(move || {
+                    let mut shard = vec![Complex::new(0.0, 0.0); 1usize << local];
+                    if rank == 0 {
+                        shard[0] = Complex::new(1.0, 0.0);
+                    }
+                    execute(rank, shard, steps, gates, outbox, inbox)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap_or_else(|_| Err("Worker panicked".to_string()))).collect()
+    });
+
+    let mut shards = Vec::with_capacity(nodes);
+    let mut amplitudes_sent = 0;
+    for outcome in outcomes {
+        let (shard, sent) = outcome?;
+        amplitudes_sent += sent;
+        shards.push(shard);
+    }
+    Ok(ShardedResult {
+        shards,
+        layout,
+        exchanges: steps.iter().filter(|s| matches!(s, Step::Exchange { .. })).count(),
+        amplitudes_sent,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharded_matches_single_node() {
+        let mut circuit = Circuit::new(7);
+        for qubit in 0..7 {
+            circuit.ry(qubit, 0.2 + 0.3 * qubit as f64);
+        }
+        // Gates on the top qubits force exchanges; CX from a global control stays local
+        circuit.h(6).cx(6, 0).cx(1, 5).rz(5, 0.7).t(6).rx(6, 1.3).cx(5, 6).cx(0, 4).s(2);
+        let expected = QuantumSimulator::ideal(7).statevector(&circuit);
+        let result = run_sharded(&circuit, 4).unwrap();
+        let gathered = result.gather();
+        let error: f64 = expected.iter().zip(&gathered).map(|(e, a)| (e - a).norm_sqr()).sum();
+        assert!(error < 1e-20);
+        let (peak, probability) = result.peak().unwrap();
+        assert_eq!(probability, gathered.iter().map(|a| a.norm_sqr()).fold(0.0, f64::max));
+        assert_eq!(gathered[peak].norm_sqr(), probability);
+        assert!(result.exchanges > 0);
+        // Each exchange moves half of every shard
+        assert_eq!(result.amplitudes_sent, result.exchanges * 4 * (1 << 5) / 2);
+
+        assert!(run_sharded(&circuit, 3).is_err());
+        let mut measured = Circuit::new(4);
+        measured.measure(0);
+        assert!(run_sharded(&measured, 2).is_err());
+
+        // Only CX may carry a control, whether the control is local or global
+        for control in [0, 3] {
+            let mut controlled = Circuit::new(4);
+            controlled.push(QuantumOperation::H, 1, Some(control));
+            assert!(run_sharded(&controlled, 2).is_err());
+        }
+    }
+}
@@ -1,5 +1,7 @@
 // Quantum Simulation Test
-use morph::quantum_simulation::{QuantumSimulator, DistributedSimulator};
+use morph::quantum_simulation::{QuantumSimulator, DistributedSimulator, Circuit, HardwareEfficientAnsatz, MaxCut, Observable, Qaoa, Vqe};
+use morph::quantum_classical::HybridComputation;
+use morph::evolutionary::CmaEs;
 use morph::core::tensor::MorphicTensor;
 use morph::quantum::qasm::QuantumOperation;
 use morph::quantum::state::QuantumState;
@@ -36,6 +38,52 @@ fn main() {
     println!("Measurement result: {}", result);
     println!("Collapsed state: {:?}", state.iter().map(|c| c.norm_sqr()).collect::<Vec<f64>>());
 
+    // Shot-based sampling: Bell pair with noisy readout, then a mid-circuit measurement
+    let mut noisy = QuantumSimulator::ideal(2);
+    noisy.readout_error = (0.02, 0.05);
+    let mut bell = Circuit::new(2);
+    bell.h(0).cx(0, 1).measure_all();
+    let counts = noisy.run(&bell, 1000).unwrap();
+    println!("Bell counts: {:?}", counts.histogram);
+
+    let mut feedforward = Circuit::new(2);
+    feedforward.h(0).measure_into(0, 0).cx(0, 1).measure_into(1, 1);
+    let counts = QuantumSimulator::ideal(2).run(&feedforward, 1000).unwrap();
+    println!("Mid-circuit counts: {:?}, most frequent {:?}", counts.histogram, counts.most_frequent());
+
+    // Clifford circuits run on the stabilizer tableau: syndrome of a bit-flip code and a large GHZ state
+    let mut code = Circuit::new(5);
+    code.h(0).cx(0, 1).cx(0, 2).x(2);
+    code.cx(0, 3).cx(1, 3).cx(1, 4).cx(2, 4).measure_into(3, 0).measure_into(4, 1);
+    let syndrome = QuantumSimulator::ideal(5).run(&code, 100).unwrap().marginal(&[0, 1]);
+    println!("Bit-flip syndrome with an error on qubit 2: {:?}", syndrome.histogram);
+
+    let ghz_qubits = 1024;
+    let mut ghz = Circuit::new(ghz_qubits);
+    ghz.h(0);
+    for qubit in 1..ghz_qubits {
+        ghz.cx(qubit - 1, qubit);
+    }
+    ghz.measure_all();
+    let counts = QuantumSimulator::ideal(ghz_qubits).run(&ghz, 3).unwrap();
+    let uniform = counts.histogram.keys().all(|k| k.chars().all(|c| c == '0') || k.chars().all(|c| c == '1'));
+    println!("{}-qubit GHZ: {} shots, all outcomes uniform: {}", ghz_qubits, counts.shots, uniform);
+
+    // Low-entanglement circuit on 64 qubits with the matrix-product backend
+    let chain = 64;
+    let mut ladder = Circuit::new(chain);
+    for qubit in 0..chain {
+        ladder.ry(qubit, 0.2 + 0.01 * qubit as f64);
+    }
+    for qubit in (0..chain - 1).step_by(2) {
+        ladder.cx(qubit, qubit + 1);
+    }
+    ladder.cx(0, chain - 1).measure_all();
+    let mut mps_simulator = QuantumSimulator::ideal(chain);
+    mps_simulator.max_bond = 16;
+    let counts = mps_simulator.run(&ladder, 20).unwrap();
+    println!("{}-qubit ladder: {} distinct outcomes in {} shots", chain, counts.histogram.len(), counts.shots);
+
     // Test distributed simulator
     let cluster = DistributedSimulator::new(4, 3);
     let operations = vec![
@@ -47,5 +95,50 @@ fn main() {
     ];
     cluster.simulate_distributed(&tensor, &operations);
 
+    // Sharded GHZ preparation agrees with the single-node state vector
+    let mut spread = Circuit::new(cluster.total_qubits());
+    spread.h(cluster.total_qubits() - 1);
+    for qubit in (1..cluster.total_qubits()).rev() {
+        spread.cx(qubit, qubit - 1);
+    }
+    let sharded = cluster.simulate_circuit(&spread).unwrap();
+    let reference = QuantumSimulator::ideal(cluster.total_qubits()).statevector(&spread);
+    let deviation: f64 = sharded.gather().iter().zip(&reference).map(|(a, b)| (a - b).norm_sqr()).sum();
+    println!("Sharded GHZ: {} exchanges, deviation from single node {:.1e}", sharded.exchanges, deviation);
+
+    // Variational eigensolver for the two-qubit H₂ Hamiltonian
+    let hamiltonian = Observable::parse(
+        "-1.052373 II + 0.397937 ZI - 0.397937 IZ - 0.011280 ZZ + 0.180931 XX",
+    ).unwrap();
+    let vqe = Vqe::new(hamiltonian, HardwareEfficientAnsatz::new(2, 1)).unwrap();
+    let hybrid = HybridComputation::new(0.5);
+    match hybrid.ground_state_energy(&mut tensor, &vqe, &mut CmaEs::new(0.3)) {
+        Ok(Some(energy)) => println!("H₂ ground-state energy: {:.6} (exact {:.6})", energy, vqe.hamiltonian.ground_energy(2).unwrap()),
+        Ok(None) => println!("H₂ ground-state energy skipped"),
+        Err(e) => println!("H₂ ground-state energy failed: {}", e),
+    }
+
+    // Shot-based estimation with qubit-wise commuting groups
+    let ground = vqe.simulator.statevector(&vqe.ansatz.circuit(&vqe.run(&mut CmaEs::new(0.3)).unwrap().parameters));
+    let groups = vqe.hamiltonian.group_commuting();
+    println!("H₂ terms measured in {} groups: {:?}", groups.len(),
+             groups.iter().map(|g| g.basis.factors.clone()).collect::<Vec<_>>());
+    for shots in [100, 1000, 10000] {
+        match vqe.hamiltonian.estimate(&ground, shots, &mut rand::thread_rng()) {
+            Ok(estimate) => println!("{:>6} shots/group: {:.4} ± {:.4}", shots, estimate.value, estimate.standard_error),
+            Err(e) => println!("Estimation failed: {}", e),
+        }
+    }
+    match vqe.hamiltonian.variance(&ground) {
+        Ok(variance) => println!("Energy variance in the VQE state: {:.2e}", variance),
+        Err(e) => println!("Variance failed: {}", e),
+    }
+
+    // QAOA for MaxCut on a weighted triangle with a pendant node
+    let graph = MaxCut::new(4, vec![(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (2, 3, 2.0)]).unwrap();
+    let qaoa = Qaoa::new(graph, 2);
+    let result = qaoa.run(&mut CmaEs::new(0.3)).unwrap();
+    println!("Optimal cut {} vs QAOA cut {} (p = {:.3})", qaoa.graph.best_cut().1, result.cut, result.probability);
+
     println!("✅ Quantum simulation tests completed!");
 }
@@ -0,0 +1,272 @@
+// Stabilizer (CHP) simulation of Clifford circuits
+#![allow(dead_code)]
+
+use crate::quantum::qasm::QuantumOperation;
+use crate::quantum_simulation::Instruction;
+use rand::Rng;
+
+/// Pauli operator on every qubit, bit-packed, with a sign
+#[derive(Debug, Clone)]
+struct Row {
+    x: Vec<u64>,
+    z: Vec<u64>,
+    /// Sign −1 when set
+    phase: bool,
+}
+
+impl Row {
+    fn new(words: usize) -> Self {
+        Row {
+            x: vec![0; words],
+            z: vec![0; words],
+            phase: false,
+        }
+    }
+
+    fn x(&self, qubit: usize) -> bool {
+        self.x[qubit / 64] >> (qubit % 64) & 1 == 1
+    }
+
+    fn z(&self, qubit: usize) -> bool {
+        self.z[qubit / 64] >> (qubit % 64) & 1 == 1
+    }
+
+    fn set(&mut self, qubit: usize, x: bool, z: bool) {
+        let (word, bit) = (qubit / 64, 1u64 << (qubit % 64));
+        self.x[word] = if x { self.x[word] | bit } else { self.x[word] & !bit };
+        self.z[word] = if z { self.z[word] | bit } else { self.z[word] & !bit };
+    }
+
+    fn clear(&mut self) {
+        self.x.iter_mut().for_each(|w| *w = 0);
+        self.z.iter_mut().for_each(|w| *w = 0);
+        self.phase = false;
+    }
+
+    /// Multiply `other` into this row, tracking the sign of the product
+    fn multiply(&mut self, other: &Row) {
+        // Sum of i-exponents picked up factor by factor; the total is 0 or 2 mod 4
+        let mut exponent: i64 = 0;
+        for word in 0..self.x.len() {
+            let (x1, z1, x2, z2) = (other.x[word], other.z[word], self.x[word], self.z[word]);
+            let (y, x_only, z_only) = (x1 & z1, x1 & !z1, !x1 & z1);
+            let plus = (y & z2 & !x2) | (x_only & z2 & x2) | (z_only & x2 & !z2);
+            let minus = (y & x2 & !z2) | (x_only & z2 & !x2) | (z_only & x2 & z2);
+            exponent += plus.count_ones() as i64 - minus.count_ones() as i64;
+            self.x[word] ^= x1;
+            self.z[word] ^= z1;
+        }
+        exponent += 2 * (self.phase as i64 + other.phase as i64);
+        self.phase = exponent.rem_euclid(4) == 2;
+    }
+
+    fn label(&self, qubits: usize) -> String {
+        let sign = if self.phase { '-' } else { '+' };
+        std::iter::once(sign)
+            .chain((0..qubits).map(|q| match (self.x(q), self.z(q)) {
+                (false, false) => 'I',
+                (true, false) => 'X',
+                (true, true) => 'Y',
+                (false, true) => 'Z',
+            }))
+            .collect()
+    }
+}
+
+/// Aaronson–Gottesman tableau of an n-qubit stabilizer state
+///
+/// Memory grows as n² bits and each gate touches 2n rows, so thousands of qubits
+/// are practical where a state vector would need 2ⁿ amplitudes.
+#[derive(Debug, Clone)]
+pub struct StabilizerState {
+    pub qubits: usize,
+    /// Destabilizers `0..n`, stabilizers `n..2n`
+    rows: Vec<Row>,
+}
+
+impl StabilizerState {
+    /// The state |0…0⟩, stabilized by Z on every qubit
+    pub fn new(qubits: usize) -> Self {
+        let words = qubits.div_ceil(64).max(1);
+        let mut rows = vec![Row::new(words); 2 * qubits];
+        for qubit in 0..qubits {
+            rows[qubit].set(qubit, true, false);
+            rows[qubits + qubit].set(qubit, false, true);
+        }
+        StabilizerState { qubits, rows }
+    }
+
+    fn check(&self, qubit: usize) -> Result<(), String> {
+        if qubit < self.qubits {
+            Ok(())
+        } else {
+            Err(format!("Qubit {} outside a {}-qubit tableau", qubit, self.qubits))
+        }
+    }
+
+    pub fn h(&mut self, qubit: usize) {
+        for row in &mut self.rows {
+            let (x, z) = (row.x(qubit), row.z(qubit));
+            row.phase ^= x && z;
+            row.set(qubit, z, x);
+        }
+    }
+
+    pub fn s(&mut self, qubit: usize) {
+        for row in &mut self.rows {
+            let (x, z) = (row.x(qubit), row.z(qubit));
+            row.phase ^= x && z;
+            row.set(qubit, x, z ^ x);
+        }
+    }
+
+    pub fn x(&mut self, qubit: usize) {
+        for row in &mut self.rows {
+            row.phase ^= row.z(qubit);
+        }
+    }
+
+    pub fn y(&mut self, qubit: usize) {
+        for row in &mut self.rows {
+            row.phase ^= row.x(qubit) ^ row.z(qubit);
+        }
+    }
+
+    pub fn z(&mut self, qubit: usize) {
+        for row in &mut self.rows {
+            row.phase ^= row.x(qubit);
+        }
+    }
+
+    pub fn cx(&mut self, control: usize, target: usize) {
+        for row in &mut self.rows {
+            let (xc, zc, xt, zt) = (row.x(control), row.z(control), row.x(target), row.z(target));
+            row.phase ^= xc && zt && (xt == zc);
+            row.set(target, xt ^ xc, zt);
+            row.set(control, xc, zc ^ zt);
+        }
+    }
+
+    /// Measure `qubit` in the Z basis, updating the tableau to the post-measurement state
+    pub fn measure<R: Rng>(&mut self, qubit: usize, rng: &mut R) -> bool {
+        let n = self.qubits;
+        match (n..2 * n).find(|&p| self.rows[p].x(qubit)) {
+            Some(p) => {
+                // Random outcome: every other row anticommuting with Z is fixed up by row p
+                let pivot = self.rows[p].clone();
+                for i in (0..2 * n).filter(|&i| i != p) {
+                    if self.rows[i].x(qubit) {
+                        self.rows[i].multiply(&pivot);
+                    }
+                }
+                self.rows[p - n] = pivot;
+                let outcome = rng.gen::<bool>();
+                let row = &mut self.rows[p];
+                row.clear();
+                row.set(qubit, false, true);
+                row.phase = outcome;
+                outcome
+            }
+            None => {
+                // Deterministic outcome: Z is a product of the stabilizers flagged by destabilizers
+                let mut scratch = Row::new(self.rows[0].x.len());
+                for i in 0..n {
+                    if self.rows[i].x(qubit) {
+                        scratch.multiply(&self.rows[i + n]);
+                    }
+                }
+                scratch.phase
+            }
+        }
+    }
+
+    /// Apply one circuit instruction; measurements return their outcome
+    pub fn apply<R: Rng>(&mut self, instruction: &Instruction, rng: &mut R) -> Result<Option<bool>, String> {
+        let target = instruction.target;
+        self.check(target)?;
+        match (&instruction.operation, instruction.control) {
+            (QuantumOperation::H, _) => self.h(target),
+            (QuantumOperation::S, _) => self.s(target),
+            (QuantumOperation::X, _) => self.x(target),
+            (QuantumOperation::Y, _) => self.y(target),
+            (QuantumOperation::Z, _) => self.z(target),
+            (QuantumOperation::CX, Some(control)) if control != target => {
+                self.check(control)?;
+                self.cx(control, target);
+            }
+            (QuantumOperation::Measure, _) => return Ok(Some(self.measure(target, rng))),
+            (operation, _) => return Err(format!("{:?} is not a Clifford operation on this backend", operation)),
+        }
+        Ok(None)
+    }
+
+    /// Signed Pauli labels of the stabilizer generators, qubit 0 first
+    pub fn stabilizers(&self) -> Vec<String> {
+        self.rows[self.qubits..].iter().map(|row| row.label(self.qubits)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quantum_simulation::{Circuit, QuantumSimulator};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_bell_stabilizers_and_deterministic_outcomes() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut bell = StabilizerState::new(2);
+        bell.h(0);
+        bell.cx(0, 1);
+        let mut labels = bell.stabilizers();
+        labels.sort();
+        assert_eq!(labels, vec!["+XX", "+ZZ"]);
+        for _ in 0..20 {
+            let mut state = bell.clone();
+            let first = state.measure(0, &mut rng);
+            assert_eq!(state.measure(1, &mut rng), first);
+            assert_eq!(state.measure(0, &mut rng), first);
+        }
+
+        // H S S H = X and S² = Z: signs must be tracked through the tableau
+        let mut flipped = StabilizerState::new(1);
+        flipped.h(0);
+        flipped.s(0);
+        flipped.s(0);
+        flipped.h(0);
+        assert!(flipped.measure(0, &mut rng));
+        let mut phased = StabilizerState::new(1);
+        phased.y(0);
+        assert_eq!(phased.stabilizers(), vec!["-Z"]);
+    }
+
+    #[test]
+    fn test_bit_flip_syndrome_and_large_ghz() {
+        // Three-qubit repetition code with an X error on qubit 1 and two syndrome ancillas
+        let mut code = Circuit::new(5);
+        code.h(0).cx(0, 1).cx(0, 2).x(1);
+        code.cx(0, 3).cx(1, 3).cx(1, 4).cx(2, 4).measure_into(3, 0).measure_into(4, 1);
+        assert!(code.is_clifford());
+        let counts = QuantumSimulator::ideal(5).run(&code, 50).unwrap();
+        assert_eq!(counts.marginal(&[0, 1]).get("11"), 50);
+
+        let qubits = 1000;
+        let mut ghz = Circuit::new(qubits);
+        ghz.h(0);
+        for qubit in 1..qubits {
+            ghz.cx(qubit - 1, qubit);
+        }
+        ghz.measure_all();
+        let counts = QuantumSimulator::ideal(qubits).run(&ghz, 4).unwrap();
+        let (zeros, ones) = ("0".repeat(qubits), "1".repeat(qubits));
+        assert_eq!(counts.get(&zeros) + counts.get(&ones), 4);
+
+        // Forcing the tableau onto a non-Clifford circuit fails instead of skipping gates
+        let mut simulator = QuantumSimulator::ideal(2);
+        simulator.backend = Some(crate::quantum_simulation::Backend::Stabilizer);
+        let mut phased = Circuit::new(2);
+        phased.h(0).t(0).measure_all();
+        assert!(simulator.run(&phased, 5).is_err());
+    }
+}